@@ -0,0 +1,77 @@
+//! Compares testing every asteroid against every bullet with going through the [SpatialGrid] first
+//! - `cargo bench --bench collision`
+//! - asteroids and bullets are spread over a large world, like [World::new_large]
+
+use asteroids::{Arena, Asteroid, KinematicGetters, SpatialGrid, World};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use macroquad::{prelude::*, rand::gen_range};
+
+const BULLET_COUNT: usize = 200;
+
+fn scene(asteroid_count: usize) -> (Arena, Vec<Asteroid>, Vec<Vec2>) {
+    rand::srand(0);
+    let arena = Arena::default();
+    let arena = Arena::new(
+        arena.dimensions().x * World::LARGE_WORLD_SCALE,
+        arena.dimensions().y * World::LARGE_WORLD_SCALE,
+    );
+    let asteroids = (0..asteroid_count)
+        .map(|_| {
+            let size = gen_range(Asteroid::MIN_SIZE, Asteroid::MAX_SIZE);
+            Asteroid::new(arena.random_position(), Vec2::ZERO, size, 0.0, 0.0)
+        })
+        .collect();
+    let bullets = (0..BULLET_COUNT).map(|_| arena.random_position()).collect();
+    (arena, asteroids, bullets)
+}
+
+fn brute_force(arena: &Arena, asteroids: &[Asteroid], bullets: &[Vec2]) -> usize {
+    let mut hits = 0;
+    for asteroid in asteroids {
+        for &bullet in bullets {
+            if asteroid.is_point_inside(bullet, arena) {
+                hits += 1;
+            }
+        }
+    }
+    hits
+}
+
+fn with_grid(arena: &Arena, asteroids: &[Asteroid], bullets: &[Vec2]) -> usize {
+    let mut grid = SpatialGrid::new(arena, World::GRID_CELL_SIZE);
+    for (index, asteroid) in asteroids.iter().enumerate() {
+        grid.insert(index, asteroid.position(), asteroid.size());
+    }
+    let bullets: Vec<(Vec2, f32)> = bullets.iter().map(|&bullet| (bullet, 0.0)).collect();
+    grid.candidate_pairs(&bullets)
+        .into_iter()
+        .filter(|&(asteroid, bullet)| asteroids[asteroid].is_point_inside(bullets[bullet].0, arena))
+        .count()
+}
+
+fn collision(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("asteroids vs 200 bullets");
+    for asteroid_count in [100, 1_000, 5_000] {
+        let (arena, asteroids, bullets) = scene(asteroid_count);
+        assert_eq!(
+            brute_force(&arena, &asteroids, &bullets),
+            with_grid(&arena, &asteroids, &bullets),
+            "the grid should find exactly the same hits"
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("brute force", asteroid_count),
+            &asteroid_count,
+            |bencher, _| bencher.iter(|| brute_force(&arena, black_box(&asteroids), &bullets)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("grid", asteroid_count),
+            &asteroid_count,
+            |bencher, _| bencher.iter(|| with_grid(&arena, black_box(&asteroids), &bullets)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, collision);
+criterion_main!(benches);
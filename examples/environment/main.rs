@@ -0,0 +1,47 @@
+//! A random agent playing the [Environment], to show how to use it and how fast it runs
+//! - `cargo run --release --example environment` plays a few episodes and prints their scores and the steps per second
+//! - `-- --pixels` also renders every step, to see what pixel observations cost
+
+use asteroids::{Actions, Environment};
+use macroquad::rand;
+use std::time::Instant;
+
+const EPISODES: u64 = 10;
+const MAX_STEPS: usize = 5000;
+
+fn main() {
+    let pixels = std::env::args().any(|arg| arg == "--pixels");
+    let mut environment = Environment::default().with_max_steps(MAX_STEPS);
+    println!("observation length: {}", environment.observation_len());
+
+    let start = Instant::now();
+    let mut total_steps = 0;
+    for seed in 0..EPISODES {
+        environment.reset(seed);
+        let mut score = 0.0;
+        let mut steps = 0;
+        loop {
+            // the actions come from the same random numbers as the world, so episodes still repeat for a seed
+            let actions = Actions::from_index(rand::gen_range(0, Actions::COUNT));
+            let (_observation, reward, done) = environment.step(actions);
+            if pixels {
+                environment.render();
+            }
+            score += reward;
+            steps += 1;
+            if done {
+                break;
+            }
+        }
+        total_steps += steps;
+        println!(
+            "episode {seed}: {steps} steps, reward {score}, lives {}",
+            environment.world().player().lives()
+        );
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    println!(
+        "{total_steps} steps in {seconds:.2}s, {:.0} steps per second",
+        total_steps as f64 / seconds
+    );
+}
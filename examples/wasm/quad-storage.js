@@ -0,0 +1,33 @@
+// Browser side of the `quad-storage-sys` crate: exposes `localStorage` to the wasm binary.
+// Needs `sapp_jsutils.js` to be loaded first for `js_object` and `get_js_object`.
+"use strict";
+
+function quad_storage_register_plugin(importObject) {
+    importObject.env.quad_storage_length = function () {
+        return localStorage.length;
+    }
+    importObject.env.quad_storage_has_key = function (i) {
+        return localStorage.key(i) != null ? 1 : 0;
+    }
+    importObject.env.quad_storage_key = function (i) {
+        return js_object(localStorage.key(i));
+    }
+    importObject.env.quad_storage_has_value = function (key) {
+        return localStorage.getItem(get_js_object(key)) != null ? 1 : 0;
+    }
+    importObject.env.quad_storage_get = function (key) {
+        return js_object(localStorage.getItem(get_js_object(key)));
+    }
+    importObject.env.quad_storage_set = function (key, value) {
+        localStorage.setItem(get_js_object(key), get_js_object(value));
+    }
+    importObject.env.quad_storage_remove = function (key) {
+        localStorage.removeItem(get_js_object(key));
+    }
+    importObject.env.quad_storage_clear = function () {
+        localStorage.clear();
+    }
+}
+
+// must match `quad_storage_crate_version` exported by quad-storage-sys 0.1.0
+miniquad_add_plugin({ register_plugin: quad_storage_register_plugin, version: (0 << 24) + (1 << 16) + 0, name: "quad_storage" });
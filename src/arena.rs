@@ -0,0 +1,73 @@
+//! The area everything moves around in, measured in logical units instead of window pixels
+//! - the arena wraps around at its edges
+//! - gameplay only ever looks at the arena, so it plays the same no matter how big the window is
+
+use crate::{SCREEN_HEIGHT, SCREEN_WIDTH};
+use macroquad::{prelude::*, rand::gen_range};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Arena {
+    dimensions: Vec2,
+}
+impl Default for Arena {
+    /// [SCREEN_WIDTH] by [SCREEN_HEIGHT]
+    fn default() -> Self {
+        Self::new(SCREEN_WIDTH as f32, SCREEN_HEIGHT as f32)
+    }
+}
+impl Arena {
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self {
+            dimensions: vec2(width, height),
+        }
+    }
+    /// `<width>x<height>`, like `1200x800`. Both have to be positive
+    pub fn parse(size: &str) -> Result<Self, String> {
+        let invalid = || format!("{size} should be a positive size like 1200x800");
+        let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+        let width: f32 = width.parse().map_err(|_| invalid())?;
+        let height: f32 = height.parse().map_err(|_| invalid())?;
+        let is_valid = |length: f32| length > 0.0 && length.is_finite();
+        if !is_valid(width) || !is_valid(height) {
+            return Err(invalid());
+        }
+        Ok(Self::new(width, height))
+    }
+    pub fn dimensions(&self) -> Vec2 {
+        self.dimensions
+    }
+    pub fn origin(&self) -> Vec2 {
+        self.dimensions / 2.0
+    }
+    /// The furthest apart two points can be, counting across the edges
+    pub fn max_distance(&self) -> f32 {
+        (self.dimensions / 2.0).length()
+    }
+    pub fn random_position(&self) -> Vec2 {
+        vec2(
+            gen_range(0.0, self.dimensions.x),
+            gen_range(0.0, self.dimensions.y),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_a_size() {
+        assert_eq!(Arena::parse("1200x800"), Ok(Arena::new(1200.0, 800.0)));
+        assert_eq!(Arena::parse("640.5x480"), Ok(Arena::new(640.5, 480.0)));
+    }
+
+    #[test]
+    fn rejects_anything_else() {
+        for size in [
+            "", "1200", "1200x", "x800", "0x800", "1200x-1", "infx800", "ax b",
+        ] {
+            assert!(Arena::parse(size).is_err(), "{size}");
+        }
+    }
+}
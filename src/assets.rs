@@ -1,14 +1,57 @@
+use crate::Sprite;
 use macroquad::prelude::*;
-use std::sync::OnceLock;
+use std::sync::{Mutex, OnceLock};
 
-static DUCK_TEXTURE: OnceLock<Texture2D> = OnceLock::new();
+static DUCK_TEXTURE: Mutex<Option<Texture2D>> = Mutex::new(None);
 const DUCK_PNG: &'static [u8] = include_bytes!("../assets/duck.png");
-pub fn duck_texture() -> &'static Texture2D {
-    DUCK_TEXTURE.get_or_init(|| Texture2D::from_file_with_format(DUCK_PNG, None))
+pub fn duck_texture() -> Texture2D {
+    let mut texture = DUCK_TEXTURE.lock().unwrap();
+    texture
+        .get_or_insert_with(|| Texture2D::from_file_with_format(DUCK_PNG, None))
+        .clone()
 }
 
 const ROCK_PNG: &'static [u8] = include_bytes!("../assets/rock.png");
-static ROCK_TEXTURE: OnceLock<Texture2D> = OnceLock::new();
-pub fn rock_texture() -> &'static Texture2D {
-    ROCK_TEXTURE.get_or_init(|| Texture2D::from_file_with_format(ROCK_PNG, None))
+static ROCK_TEXTURE: Mutex<Option<Texture2D>> = Mutex::new(None);
+pub fn rock_texture() -> Texture2D {
+    let mut texture = ROCK_TEXTURE.lock().unwrap();
+    texture
+        .get_or_insert_with(|| Texture2D::from_file_with_format(ROCK_PNG, None))
+        .clone()
 }
+
+/// Draws `sprite` with `png` from now on instead of the png built into the game. Needs a window.
+/// Nothing changes if `png` can't be decoded
+pub fn replace_texture(sprite: Sprite, png: &[u8]) -> Result<(), macroquad::Error> {
+    let image = Image::from_file_with_format(png, None)?;
+    let texture = match sprite {
+        Sprite::Duck => &DUCK_TEXTURE,
+        Sprite::Rock => &ROCK_TEXTURE,
+    };
+    *texture.lock().unwrap() = Some(Texture2D::from_image(&image));
+    Ok(())
+}
+
+/// The built in duck.png decoded without a graphics context. Not affected by [replace_texture]
+static DUCK_IMAGE: OnceLock<Image> = OnceLock::new();
+pub fn duck_image() -> &'static Image {
+    DUCK_IMAGE.get_or_init(|| {
+        Image::from_file_with_format(DUCK_PNG, None).expect("duck.png should be a valid image")
+    })
+}
+
+/// The built in rock.png decoded without a graphics context. Not affected by [replace_texture]
+static ROCK_IMAGE: OnceLock<Image> = OnceLock::new();
+pub fn rock_image() -> &'static Image {
+    ROCK_IMAGE.get_or_init(|| {
+        Image::from_file_with_format(ROCK_PNG, None).expect("rock.png should be a valid image")
+    })
+}
+
+pub const FIRE_WAV: &[u8] = include_bytes!("../assets/fire.wav");
+pub const THRUST_WAV: &[u8] = include_bytes!("../assets/thrust.wav");
+pub const BANG_LARGE_WAV: &[u8] = include_bytes!("../assets/bang_large.wav");
+pub const BANG_MEDIUM_WAV: &[u8] = include_bytes!("../assets/bang_medium.wav");
+pub const BANG_SMALL_WAV: &[u8] = include_bytes!("../assets/bang_small.wav");
+pub const DEATH_WAV: &[u8] = include_bytes!("../assets/death.wav");
+pub const EXTRA_LIFE_WAV: &[u8] = include_bytes!("../assets/extra_life.wav");
@@ -6,18 +6,21 @@
 //! - draw a line between each vertex
 
 use crate::{
-    is_point_in_polygon, polar_vec2, rock_texture, Bullet, Draw, Kinematic, KinematicGetters,
-    KinematicMutators, Player, RotationMatrix, FRAC_SQRT3_2,
+    do_polygons_overlap, is_point_in_polygon, is_segment_in_polygon, polar_vec2, toroidal_delta,
+    toroidal_distance, Arena, Collider, Components, Draw, Entity, GameConfig, Kinematic,
+    KinematicGetters, KinematicMutators, Player, Renderer, RotationMatrix, Sprite, VectorDisplay,
+    FRAC_SQRT3_2,
 };
 use macroquad::{prelude::*, rand::gen_range};
+use serde::{Deserialize, Serialize};
 use std::f32::consts::{FRAC_PI_2, TAU};
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Asteroid {
-    kinematic: Kinematic,
+    components: Components,
     size: f32,
     orientation: f32,
     rotation_speed: f32,
-    has_collided: bool,
 }
 impl Asteroid {
     pub const UNIT_VERTICES: [Vec2; 6] = [
@@ -38,20 +41,39 @@ impl Asteroid {
     pub const MIN_SIZE: f32 = Player::SIZE / 2.0;
     pub const MAX_SIZE: f32 = Player::SIZE * 4.0;
 
-    const CHILD_SIZE_FACTOR: f32 = 1.0 / 2.0;
+    /// Only the default, see [GameConfig]
+    pub const CHILD_SIZE_FACTOR: f32 = 1.0 / 2.0;
     const CHILD_ROTATION_SPEED_FACTOR: f32 = 2.0 / 3.0;
+    /// [Self::many_random_away_from] asks for at most this fraction of [Arena::max_distance], so a small arena still has room
+    const MAX_AWAY_FRACTION: f32 = 1.0 / 2.0;
 }
 impl Asteroid {
-    pub fn many_random(count: usize) -> Vec<Self> {
-        (0..count).map(|_| Asteroid::random()).collect()
-    }
-    pub fn random() -> Self {
+    pub fn many_random(count: usize, arena: &Arena) -> Vec<Self> {
+        (0..count).map(|_| Asteroid::random(arena)).collect()
+    }
+    /// Like [Self::many_random] but every asteroid is at least `distance` away from `point`, counting across the edges.
+    /// In an arena too small for that, half as far as anything can be from `point` instead
+    pub fn many_random_away_from(
+        count: usize,
+        point: Vec2,
+        distance: f32,
+        arena: &Arena,
+    ) -> Vec<Self> {
+        let distance = distance.min(arena.max_distance() * Self::MAX_AWAY_FRACTION);
+        (0..count)
+            .map(|_| loop {
+                let asteroid = Asteroid::random(arena);
+                let dimensions = arena.dimensions();
+                if toroidal_distance(asteroid.position(), point, dimensions) >= distance {
+                    break asteroid;
+                }
+            })
+            .collect()
+    }
+    pub fn random(arena: &Arena) -> Self {
         let size = gen_range(Self::MIN_SIZE, Self::MAX_SIZE);
 
-        let position = vec2(
-            gen_range(0.0, screen_width()),
-            gen_range(0.0, screen_height()),
-        );
+        let position = arena.random_position();
 
         let speed = gen_range(Self::MIN_SPEED, Self::MAX_SPEED);
         let angle = gen_range(0.0, TAU);
@@ -60,28 +82,39 @@ impl Asteroid {
         let orientation = gen_range(0.0, TAU);
         let rotation_speed = gen_range(Self::MIN_ROTATION_SPEED, Self::MAX_ROTATION_SPEED);
 
+        Self::new(position, velocity, size, orientation, rotation_speed)
+    }
+    /// An asteroid with everything chosen, for when [Self::random] won't do
+    pub fn new(
+        position: Vec2,
+        velocity: Vec2,
+        size: f32,
+        orientation: f32,
+        rotation_speed: f32,
+    ) -> Self {
+        let kinematic = Kinematic::new(position, velocity, Vec2::ZERO);
         Self {
-            kinematic: Kinematic::new(position, velocity, Vec2::ZERO),
+            // the vertices are `size` away from the center
+            components: Components::new(kinematic, Collider::new(size)),
             size,
             orientation,
             rotation_speed,
-            has_collided: false,
         }
     }
     /// Creates another [Asteroid] with
     /// - the same position as `self`
     /// - a given `velocity`
-    /// - size scaled by [Self::CHILD_SIZE_FACTOR]
+    /// - size scaled by `size_factor`
     /// - rotation speed scaled by [Self::CHILD_ROTATION_SPEED_FACTOR]
     /// - has **not** collided
-    pub fn create_child(&self, velocity: Vec2) -> Self {
-        Self {
-            kinematic: Kinematic::new(self.position(), velocity, Vec2::ZERO),
-            size: self.size * Self::CHILD_SIZE_FACTOR,
-            orientation: self.orientation,
-            rotation_speed: self.rotation_speed * Self::CHILD_ROTATION_SPEED_FACTOR,
-            has_collided: false,
-        }
+    pub fn create_child(&self, velocity: Vec2, size_factor: f32) -> Self {
+        Self::new(
+            self.position(),
+            velocity,
+            self.size * size_factor,
+            self.orientation,
+            self.rotation_speed * Self::CHILD_ROTATION_SPEED_FACTOR,
+        )
     }
     pub fn size(&self) -> f32 {
         self.size
@@ -89,15 +122,23 @@ impl Asteroid {
     pub fn orientation(&self) -> f32 {
         self.orientation
     }
+    /// The area of the hexagon
+    pub fn area(&self) -> f32 {
+        3.0 * FRAC_SQRT3_2 * self.size * self.size
+    }
+    /// Smaller asteroids are harder to hit so they are worth more
+    pub fn points(&self) -> usize {
+        if self.size > Self::MAX_SIZE / 2.0 {
+            20
+        } else if self.size > Self::MAX_SIZE / 4.0 {
+            50
+        } else {
+            100
+        }
+    }
     pub fn is_too_small(&self) -> bool {
         self.size < Self::MIN_SIZE
     }
-    pub fn has_collided(&self) -> bool {
-        self.has_collided
-    }
-    pub fn is_alive(&self) -> bool {
-        !self.has_collided && self.size >= Self::MIN_SIZE
-    }
     pub fn vertices(&self) -> [Vec2; 6] {
         let rotation = self.orientation.rotation_matrix();
         let position = self.position();
@@ -105,11 +146,33 @@ impl Asteroid {
 
         Self::UNIT_VERTICES.map(|vertex| (rotation * (vertex * scale)) + position)
     }
-    pub fn is_point_inside(&self, point: Vec2) -> bool {
+    /// Also true if `point` is inside one of the copies on the other side of an edge the asteroid crosses
+    pub fn is_point_inside(&self, point: Vec2, arena: &Arena) -> bool {
+        // check the copy of `point` that is closest to the asteroid
+        let position = self.position();
+        let point = position + toroidal_delta(position, point, arena.dimensions());
         is_point_in_polygon(point, &self.vertices())
     }
+    /// Does the polygon with `polygon_vertices` around `center` overlap the asteroid? Checked across the edges like [Self::is_point_inside]
+    pub fn overlaps_polygon(&self, center: Vec2, polygon_vertices: &[Vec2], arena: &Arena) -> bool {
+        // move the polygon to the copy that is closest to the asteroid
+        let position = self.position();
+        let offset = position + toroidal_delta(position, center, arena.dimensions()) - center;
+        let polygon_vertices: Vec<Vec2> = polygon_vertices
+            .iter()
+            .map(|&vertex| vertex + offset)
+            .collect();
+        do_polygons_overlap(&polygon_vertices, &self.vertices())
+    }
+    /// Did something that just moved by `motion` to end up at `point` pass through the asteroid on the way?
+    /// `motion` is relative to the asteroid, and checked across the edges like [Self::is_point_inside]
+    pub fn is_swept_point_inside(&self, point: Vec2, motion: Vec2, arena: &Arena) -> bool {
+        let position = self.position();
+        let end = position + toroidal_delta(position, point, arena.dimensions());
+        is_segment_in_polygon(end - motion, end, &self.vertices())
+    }
     /// Returns two [Asteroid]s at the same position as `self` with opposite velocities perpendicular to `bullet_velocity`
-    pub fn split(&self, bullet_velocity: Vec2) -> [Self; 2] {
+    pub fn split(&self, bullet_velocity: Vec2, config: &GameConfig) -> [Self; 2] {
         // randomly generate a speed for the children
         let speed = gen_range(Self::MIN_SPEED, Self::MAX_SPEED);
 
@@ -121,59 +184,68 @@ impl Asteroid {
 
         let velocity = speed * direction;
 
-        [self.create_child(velocity), self.create_child(-velocity)]
+        let size_factor = config.asteroid.child_size_factor;
+        [
+            self.create_child(velocity, size_factor),
+            self.create_child(-velocity, size_factor),
+        ]
     }
 }
 impl Asteroid {
-    pub fn set_collided(&mut self) {
-        self.has_collided = true;
-    }
-    pub fn step(&mut self) {
+    pub fn step(&mut self, arena: &Arena) {
         self.rotate();
         self.cap_speed(Self::MAX_SPEED);
-        self.keep_on_screen();
-        self.step_motion();
+        self.step_components(arena);
     }
     pub fn rotate(&mut self) {
         self.orientation += self.rotation_speed;
     }
 }
-impl KinematicGetters for Asteroid {
-    fn kinematic(&self) -> &Kinematic {
-        &self.kinematic
+impl Entity for Asteroid {
+    fn components(&self) -> &Components {
+        &self.components
     }
-}
-impl KinematicMutators for Asteroid {
-    fn kinematic_mut(&mut self) -> &mut Kinematic {
-        &mut self.kinematic
+    fn components_mut(&mut self) -> &mut Components {
+        &mut self.components
+    }
+    /// Also dies once it's split too small
+    fn is_alive(&self) -> bool {
+        !self.has_collided() && !self.is_too_small()
     }
 }
 impl Draw for Asteroid {
     /// # Example
     /// <img src="https://i.imgur.com/sI2p3qU.png">
-    fn draw(&self) {
-        let [v1, v2, v3, v4, v5, v6] = self.vertices();
-        draw_triangle(v1, v2, v3, WHITE);
-        draw_triangle(v1, v3, v5, WHITE);
-        draw_triangle(v1, v6, v5, WHITE);
-        draw_triangle(v3, v4, v5, WHITE);
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_polygon(&self.vertices(), WHITE);
 
         const TEXTURE_SCALE: f32 = 1.4;
-        let texture_offset = self.size * (TEXTURE_SCALE / 2.0);
-        let texture_position = self.position() - texture_offset;
-        draw_texture_ex(
-            rock_texture(),
-            texture_position.x,
-            texture_position.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(Vec2::splat(self.size * TEXTURE_SCALE)),
-                source: None,
-                rotation: self.orientation - FRAC_PI_2,
-                flip_x: false,
-                flip_y: true,
-                pivot: None,
-            },
+        renderer.draw_sprite(
+            Sprite::Rock,
+            self.position(),
+            self.size * TEXTURE_SCALE,
+            self.orientation - FRAC_PI_2,
         );
     }
+    fn draw_outline(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_polygon_lines(&self.vertices(), VectorDisplay::LINE_THICKNESS, WHITE);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn many_random_away_from_fits_in_a_small_arena() {
+        let arena = Arena::new(200.0, 200.0);
+        let point = arena.origin();
+        // further than anything can be from the middle of this arena
+        let asteroids = Asteroid::many_random_away_from(4, point, 160.0, &arena);
+        assert_eq!(asteroids.len(), 4);
+        for asteroid in &asteroids {
+            let distance = toroidal_distance(asteroid.position(), point, arena.dimensions());
+            assert!(distance >= arena.max_distance() / 2.0);
+        }
+    }
 }
@@ -0,0 +1,221 @@
+//! Sound effects and volume mixing
+//! - [SoundEffect] names every sound the game can make
+//! - [AudioBackend] actually makes noise, so it can be swapped for [SilentBackend] without a window or speakers
+//! - [Mixer] applies master, music and sfx volumes before handing sounds to its backend
+
+use crate::{
+    beat_high_wav, beat_low_wav, Asteroid, GameEvent, BANG_LARGE_WAV, BANG_MEDIUM_WAV,
+    BANG_SMALL_WAV, DEATH_WAV, EXTRA_LIFE_WAV, FIRE_WAV, THRUST_WAV,
+};
+use macroquad::audio::{
+    load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound,
+};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SoundEffect {
+    Fire,
+    Thrust,
+    BangLarge,
+    BangMedium,
+    BangSmall,
+    Death,
+    ExtraLife,
+    BeatLow,
+    BeatHigh,
+}
+impl SoundEffect {
+    pub const ALL: [Self; 9] = [
+        Self::Fire,
+        Self::Thrust,
+        Self::BangLarge,
+        Self::BangMedium,
+        Self::BangSmall,
+        Self::Death,
+        Self::ExtraLife,
+        Self::BeatLow,
+        Self::BeatHigh,
+    ];
+
+    /// The sound for something that happened in the world, if it has one
+    pub fn for_event(event: &GameEvent) -> Option<Self> {
+        match *event {
+            GameEvent::BulletFired { .. } => Some(Self::Fire),
+            GameEvent::AsteroidDestroyed { size, .. } => Some(Self::asteroid_break(size)),
+            GameEvent::PlayerDied { .. } => Some(Self::Death),
+            GameEvent::ExtraLifeGained { .. } => Some(Self::ExtraLife),
+            GameEvent::PickupCollected { .. } | GameEvent::WaveCleared { .. } => None,
+        }
+    }
+    /// Bigger asteroids make a lower pitched bang
+    pub fn asteroid_break(size: f32) -> Self {
+        if size > Asteroid::MAX_SIZE / 2.0 {
+            Self::BangLarge
+        } else if size > Asteroid::MAX_SIZE / 4.0 {
+            Self::BangMedium
+        } else {
+            Self::BangSmall
+        }
+    }
+    pub fn channel(&self) -> Channel {
+        match self {
+            Self::BeatLow | Self::BeatHigh => Channel::Music,
+            _ => Channel::Sfx,
+        }
+    }
+    /// The wav file this effect is loaded from. Music is synthesized, everything else is embedded
+    pub fn wav(&self) -> &'static [u8] {
+        match self {
+            Self::Fire => FIRE_WAV,
+            Self::Thrust => THRUST_WAV,
+            Self::BangLarge => BANG_LARGE_WAV,
+            Self::BangMedium => BANG_MEDIUM_WAV,
+            Self::BangSmall => BANG_SMALL_WAV,
+            Self::Death => DEATH_WAV,
+            Self::ExtraLife => EXTRA_LIFE_WAV,
+            Self::BeatLow => beat_low_wav(),
+            Self::BeatHigh => beat_high_wav(),
+        }
+    }
+}
+
+/// Every [SoundEffect] belongs to one channel with its own volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Music,
+    Sfx,
+}
+
+pub trait AudioBackend {
+    fn play(&mut self, effect: SoundEffect, volume: f32, looped: bool);
+    fn stop(&mut self, effect: SoundEffect);
+    /// Change the volume of an effect that is already playing
+    fn set_volume(&mut self, effect: SoundEffect, volume: f32);
+}
+
+/// Plays nothing. Useful when there is no window or audio device
+pub struct SilentBackend;
+impl AudioBackend for SilentBackend {
+    fn play(&mut self, _effect: SoundEffect, _volume: f32, _looped: bool) {}
+    fn stop(&mut self, _effect: SoundEffect) {}
+    fn set_volume(&mut self, _effect: SoundEffect, _volume: f32) {}
+}
+
+/// Plays sounds through macroquad's audio context
+pub struct MacroquadBackend {
+    sounds: HashMap<SoundEffect, Sound>,
+}
+impl MacroquadBackend {
+    /// Decodes every [SoundEffect]. Effects that fail to decode are skipped and stay silent
+    pub async fn load() -> Self {
+        let mut sounds = HashMap::new();
+        for effect in SoundEffect::ALL {
+            match load_sound_from_bytes(effect.wav()).await {
+                Ok(sound) => {
+                    sounds.insert(effect, sound);
+                }
+                Err(error) => eprintln!("failed to load {:?} sound: {}", effect, error),
+            }
+        }
+        Self { sounds }
+    }
+}
+impl AudioBackend for MacroquadBackend {
+    fn play(&mut self, effect: SoundEffect, volume: f32, looped: bool) {
+        if let Some(sound) = self.sounds.get(&effect) {
+            play_sound(sound, PlaySoundParams { looped, volume });
+        }
+    }
+    fn stop(&mut self, effect: SoundEffect) {
+        if let Some(sound) = self.sounds.get(&effect) {
+            stop_sound(sound);
+        }
+    }
+    fn set_volume(&mut self, effect: SoundEffect, volume: f32) {
+        if let Some(sound) = self.sounds.get(&effect) {
+            set_sound_volume(sound, volume);
+        }
+    }
+}
+
+pub struct Mixer {
+    backend: Box<dyn AudioBackend>,
+    master_volume: f32,
+    music_volume: f32,
+    sfx_volume: f32,
+    is_thrusting: bool,
+}
+impl Mixer {
+    pub const DEFAULT_VOLUME: f32 = 0.8;
+    pub const VOLUME_STEP: f32 = 0.1;
+}
+impl Mixer {
+    pub fn new(backend: Box<dyn AudioBackend>) -> Self {
+        Self {
+            backend,
+            master_volume: Self::DEFAULT_VOLUME,
+            music_volume: Self::DEFAULT_VOLUME,
+            sfx_volume: Self::DEFAULT_VOLUME,
+            is_thrusting: false,
+        }
+    }
+    pub fn silent() -> Self {
+        Self::new(Box::new(SilentBackend))
+    }
+    pub fn master_volume(&self) -> f32 {
+        self.master_volume
+    }
+    pub fn music_volume(&self) -> f32 {
+        self.music_volume
+    }
+    pub fn sfx_volume(&self) -> f32 {
+        self.sfx_volume
+    }
+    /// The volume `effect` actually plays at after mixing
+    pub fn effective_volume(&self, effect: SoundEffect) -> f32 {
+        let channel_volume = match effect.channel() {
+            Channel::Music => self.music_volume,
+            Channel::Sfx => self.sfx_volume,
+        };
+        self.master_volume * channel_volume
+    }
+}
+impl Mixer {
+    /// Play `effect` once
+    pub fn play(&mut self, effect: SoundEffect) {
+        let volume = self.effective_volume(effect);
+        self.backend.play(effect, volume, false);
+    }
+    /// Start or stop the looping [SoundEffect::Thrust]
+    pub fn set_thrust(&mut self, is_thrusting: bool) {
+        if is_thrusting == self.is_thrusting {
+            return;
+        }
+        self.is_thrusting = is_thrusting;
+        if is_thrusting {
+            let volume = self.effective_volume(SoundEffect::Thrust);
+            self.backend.play(SoundEffect::Thrust, volume, true);
+        } else {
+            self.backend.stop(SoundEffect::Thrust);
+        }
+    }
+    /// All volumes are clamped between 0 and 1
+    pub fn set_master_volume(&mut self, volume: f32) {
+        self.master_volume = volume.clamp(0.0, 1.0);
+        self.update_looping_volume();
+    }
+    pub fn set_music_volume(&mut self, volume: f32) {
+        self.music_volume = volume.clamp(0.0, 1.0);
+        self.update_looping_volume();
+    }
+    pub fn set_sfx_volume(&mut self, volume: f32) {
+        self.sfx_volume = volume.clamp(0.0, 1.0);
+        self.update_looping_volume();
+    }
+    fn update_looping_volume(&mut self) {
+        if self.is_thrusting {
+            let volume = self.effective_volume(SoundEffect::Thrust);
+            self.backend.set_volume(SoundEffect::Thrust, volume);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Plays many games without a window with a computer [Controller], to measure how balance changes affect the game
+//! - every game starts from its own seed, so the same arguments always give the same numbers
+//! - plays by `config.toml` like the game does, with `--set` on top. Constants like `Asteroid::MAX_SPEED` need a rebuild,
+//!   which `cargo run` does anyway
+//! - reports how long the ship survived, the spread of scores and waves, and how many bullets hit an asteroid
+
+use asteroids::{Actions, Arena, Autopilot, Controller, GameConfig, GameEvent, World};
+use macroquad::rand;
+use std::{process::ExitCode, time::Instant};
+
+/// The game steps once per frame at 60 frames per second
+const STEPS_PER_SECOND: f32 = 60.0;
+
+const USAGE: &str = "\
+usage: cargo run --release --bin benchmark -- [options]
+  --controller <autopilot|random|idle>  who flies the ship, autopilot by default
+  --games <count>                       how many games to play, 20 by default
+  --seed <number>                       the first game's seed, the rest count up from it. 0 by default
+  --max-steps <count>                   a game still going after this many steps is stopped, 36000 (10 minutes) by default
+  --set <section.key>=<value>           changes a config value, can be repeated
+  --verbose                             also prints every game
+  --help                                prints this";
+
+struct Options {
+    controller: String,
+    games: u64,
+    seed: u64,
+    max_steps: usize,
+    config: GameConfig,
+    is_verbose: bool,
+}
+impl Options {
+    /// [None] when `--help` was asked for instead
+    fn parse(mut arguments: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        // the browser can't run this, but it still gets built for it
+        #[cfg(target_arch = "wasm32")]
+        let config = GameConfig::default();
+        #[cfg(not(target_arch = "wasm32"))]
+        let config = GameConfig::load()
+            .map_err(|error| format!("{}: {}", GameConfig::PATH, error.message()))?;
+        let mut options = Self {
+            controller: String::from("autopilot"),
+            games: 20,
+            seed: 0,
+            max_steps: 36_000,
+            config,
+            is_verbose: false,
+        };
+        while let Some(argument) = arguments.next() {
+            if argument == "--verbose" {
+                options.is_verbose = true;
+                continue;
+            }
+            if argument == "--help" {
+                return Ok(None);
+            }
+            let value = arguments
+                .next()
+                .ok_or_else(|| format!("{argument} needs a value"))?;
+            match argument.as_str() {
+                "--controller" => options.controller = value,
+                "--games" => options.games = parse_number(&value)?,
+                "--seed" => options.seed = parse_number(&value)?,
+                "--max-steps" => options.max_steps = parse_number(&value)?,
+                "--set" => {
+                    let (name, value) = value
+                        .split_once('=')
+                        .ok_or_else(|| format!("{value} should look like section.key=value"))?;
+                    options.config.set(name, value)?;
+                }
+                _ => return Err(format!("unknown option {argument}")),
+            }
+        }
+        // fail before playing anything
+        options.create_controller()?;
+        options.last_seed()?;
+        Ok(Some(options))
+    }
+    /// The seed of the last game, as long as counting up to it fits in a [u64]
+    fn last_seed(&self) -> Result<u64, String> {
+        self.seed
+            .checked_add(self.games.saturating_sub(1))
+            .ok_or_else(|| format!("--seed {} is too big for {} games", self.seed, self.games))
+    }
+    fn create_controller(&self) -> Result<Box<dyn Controller>, String> {
+        match self.controller.as_str() {
+            "autopilot" => Ok(Box::new(Autopilot::default())),
+            "random" => Ok(Box::new(Random)),
+            "idle" => Ok(Box::new(Idle)),
+            name => Err(format!("unknown controller {name}")),
+        }
+    }
+}
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{value} is not a valid number"))
+}
+
+/// Presses a random combination of keys every step
+struct Random;
+impl Controller for Random {
+    fn actions(&mut self, _world: &World) -> Actions {
+        Actions::from_index(rand::gen_range(0, Actions::COUNT))
+    }
+}
+/// Never does anything, for how long the asteroids take on their own
+struct Idle;
+impl Controller for Idle {
+    fn actions(&mut self, _world: &World) -> Actions {
+        Actions::default()
+    }
+}
+
+/// How one game went
+struct Outcome {
+    steps: usize,
+    is_over: bool,
+    score: usize,
+    wave: usize,
+    bullets_fired: usize,
+    asteroids_hit: usize,
+}
+impl Outcome {
+    fn play(seed: u64, options: &Options) -> Self {
+        rand::srand(seed);
+        let mut world = World::new(Arena::default());
+        world.set_config(options.config.clone());
+        let mut controller = options
+            .create_controller()
+            .expect("checked while parsing the options");
+
+        let mut outcome = Self {
+            steps: 0,
+            is_over: false,
+            score: 0,
+            wave: 0,
+            bullets_fired: 0,
+            asteroids_hit: 0,
+        };
+        while outcome.steps < options.max_steps && !world.is_over() {
+            let actions = controller.actions(&world);
+            world.apply_actions(actions);
+            world.step();
+            outcome.steps += 1;
+            for event in world.take_events() {
+                match event {
+                    GameEvent::BulletFired { .. } => outcome.bullets_fired += 1,
+                    GameEvent::AsteroidDestroyed { .. } => outcome.asteroids_hit += 1,
+                    _ => {}
+                }
+            }
+        }
+        outcome.is_over = world.is_over();
+        outcome.score = world.score();
+        outcome.wave = world.wave();
+        outcome
+    }
+    fn seconds(&self) -> f32 {
+        self.steps as f32 / STEPS_PER_SECOND
+    }
+}
+
+/// Mean, minimum, quartiles and maximum of some numbers
+fn distribution(mut values: Vec<f32>) -> String {
+    values.sort_by(f32::total_cmp);
+    let mean = values.iter().sum::<f32>() / values.len() as f32;
+    // the nearest rank
+    let percentile =
+        |fraction: f32| values[((values.len() - 1) as f32 * fraction).round() as usize];
+    format!(
+        "mean {mean:.1}  min {:.1}  25% {:.1}  median {:.1}  75% {:.1}  max {:.1}",
+        percentile(0.0),
+        percentile(0.25),
+        percentile(0.5),
+        percentile(0.75),
+        percentile(1.0),
+    )
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("plays games with a computer controller and reports how they went\n{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(error) => {
+            eprintln!("{error}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if options.games == 0 {
+        println!("no games to play");
+        return ExitCode::SUCCESS;
+    }
+    let last_seed = options.last_seed().expect("checked when parsing");
+    println!(
+        "{}: {} games, seeds {} to {last_seed}, at most {} steps each",
+        options.controller, options.games, options.seed, options.max_steps
+    );
+
+    let start = Instant::now();
+    let outcomes: Vec<Outcome> = (options.seed..=last_seed)
+        .map(|seed| {
+            let outcome = Outcome::play(seed, &options);
+            if options.is_verbose {
+                println!(
+                    "  seed {seed}: {:.1}s{}, score {}, wave {}, {} of {} bullets hit",
+                    outcome.seconds(),
+                    if outcome.is_over { "" } else { " (stopped)" },
+                    outcome.score,
+                    outcome.wave,
+                    outcome.asteroids_hit,
+                    outcome.bullets_fired,
+                );
+            }
+            outcome
+        })
+        .collect();
+    let elapsed = start.elapsed().as_secs_f32();
+
+    let survived = outcomes.iter().filter(|outcome| !outcome.is_over).count();
+    let survival = outcomes.iter().map(Outcome::seconds).collect();
+    let scores = outcomes
+        .iter()
+        .map(|outcome| outcome.score as f32)
+        .collect();
+    let waves = outcomes.iter().map(|outcome| outcome.wave as f32).collect();
+    let bullets_fired: usize = outcomes.iter().map(|outcome| outcome.bullets_fired).sum();
+    let asteroids_hit: usize = outcomes.iter().map(|outcome| outcome.asteroids_hit).sum();
+    let accuracy = if bullets_fired > 0 {
+        asteroids_hit as f32 / bullets_fired as f32 * 100.0
+    } else {
+        0.0
+    };
+
+    println!("survival (s)  {}", distribution(survival));
+    println!("              {survived} still alive at the step limit");
+    println!("score         {}", distribution(scores));
+    println!("wave          {}", distribution(waves));
+    println!(
+        "accuracy      {accuracy:.1}%, {asteroids_hit} of {bullets_fired} bullets hit an asteroid"
+    );
+    println!(
+        "played {} steps in {elapsed:.1}s",
+        outcomes.iter().map(|outcome| outcome.steps).sum::<usize>()
+    );
+    ExitCode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[&str]) -> Result<Option<Options>, String> {
+        Options::parse(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    #[test]
+    fn help_is_not_an_error() {
+        assert!(matches!(parse(&["--games", "5", "--help"]), Ok(None)));
+    }
+
+    #[test]
+    fn seeds_past_the_last_u64_are_rejected() {
+        let max = u64::MAX.to_string();
+        assert!(parse(&["--seed", &max, "--games", "2"]).is_err());
+        let options = parse(&["--seed", &max, "--games", "1"]).unwrap().unwrap();
+        assert_eq!(options.last_seed(), Ok(u64::MAX));
+    }
+}
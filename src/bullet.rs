@@ -5,13 +5,17 @@
 //! - travels in the direction of the player (an angle)
 //! - disappears after n frames
 
-use crate::{polar_vec2, Draw, Kinematic, KinematicGetters, KinematicMutators, Player};
+use crate::{
+    polar_vec2, Arena, Collider, Components, Draw, Entity, GameConfig, Kinematic, KinematicGetters,
+    Lifetime, Player, Renderer,
+};
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bullet {
-    kinematic: Kinematic,
-    frames_left: usize,
-    has_collided: bool,
+    components: Components,
 }
+/// [Self::SPEED] and [Self::FRAMES_ALIVE] are only defaults, see [GameConfig]
 impl Bullet {
     pub const SIZE: f32 = Player::SIZE / 10.0;
     pub const SPEED: f32 = Player::MAX_SPEED / 4.0;
@@ -21,49 +25,44 @@ impl Bullet {
     pub fn many_new() -> Vec<Self> {
         Vec::new()
     }
-    pub fn is_too_old(&self) -> bool {
-        self.frames_left == 0
-    }
-    pub fn has_collided(&self) -> bool {
-        self.has_collided
+    /// Fired from the front of `player` in the direction it is facing
+    pub fn fired_by(player: &Player, config: &GameConfig) -> Self {
+        let player_front = player.front_vertex();
+        let speed = config.bullet.speed + player.speed();
+        let velocity = polar_vec2(speed, player.orientation());
+
+        let kinematic = Kinematic::new(player_front, velocity, Vec2::ZERO);
+        let collider = Collider::new(Self::SIZE);
+        Bullet {
+            components: Components::new(kinematic, collider)
+                .with_lifetime(Lifetime::new(config.bullet.frames_alive)),
+        }
     }
-    pub fn is_alive(&self) -> bool {
-        !self.has_collided && self.frames_left > 0
+    /// How far the bullet moved in its last [Self::step]. Zero if it was only just fired
+    pub fn motion(&self) -> Vec2 {
+        if self.lifetime().is_some_and(Lifetime::is_new) {
+            Vec2::ZERO
+        } else {
+            self.velocity()
+        }
     }
-    pub fn set_collided(&mut self) {
-        self.has_collided = true;
+    pub fn is_too_old(&self) -> bool {
+        self.lifetime().is_some_and(Lifetime::is_over)
     }
-    pub fn step(&mut self) {
-        self.step_motion();
-        self.keep_on_screen();
-        self.frames_left = self.frames_left.saturating_sub(1);
+    pub fn step(&mut self, arena: &Arena) {
+        self.step_components(arena);
     }
 }
-impl KinematicGetters for Bullet {
-    fn kinematic(&self) -> &Kinematic {
-        &self.kinematic
+impl Entity for Bullet {
+    fn components(&self) -> &Components {
+        &self.components
     }
-}
-impl KinematicMutators for Bullet {
-    fn kinematic_mut(&mut self) -> &mut Kinematic {
-        &mut self.kinematic
+    fn components_mut(&mut self) -> &mut Components {
+        &mut self.components
     }
 }
 impl Draw for Bullet {
-    fn draw(&self) {
-        let position = self.position();
-        draw_circle(position.x, position.y, Self::SIZE, WHITE);
-    }
-}
-impl From<&Player> for Bullet {
-    fn from(player: &Player) -> Self {
-        let player_front = player.front_vertex();
-        let velocity = polar_vec2(Self::SPEED + player.speed(), player.orientation());
-
-        Bullet {
-            kinematic: Kinematic::new(player_front, velocity, Vec2::ZERO),
-            frames_left: Self::FRAMES_ALIVE,
-            has_collided: false,
-        }
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        renderer.draw_circle(self.position(), Self::SIZE, WHITE);
     }
 }
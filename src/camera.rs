@@ -0,0 +1,226 @@
+//! What part of the arena is on screen, and how it moves
+//! - the view is a rectangle of the arena that gets scaled to fit the window by a letterboxed [Camera2D]
+//! - fixed: the view is the whole arena and never moves
+//! - following: the view is smaller than the arena and smoothly follows the player across the edges
+//! - shake: hits and deaths add trauma, which shakes the view and wears off over time
+//! - zoom: smoothly eases towards a target zoom, so a sudden zoom settles back on its own. A following camera
+//!   zooms out as the player speeds up, to see further ahead
+//! - world objects are drawn through a [CameraRenderer] so all of this works with any [Renderer]. Text like the HUD is drawn without it
+
+use crate::{polar_vec2, toroidal_delta, Arena, Renderer, Sprite};
+use macroquad::{prelude::*, rand::gen_range};
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum CameraMode {
+    /// Always centered on the arena
+    #[default]
+    Fixed,
+    /// Centered on the player
+    Follow,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameCamera {
+    mode: CameraMode,
+    /// The size of the visible part of the arena at a zoom of 1
+    view: Vec2,
+    /// The point in the arena at the middle of the view, not counting shake
+    center: Vec2,
+    zoom: f32,
+    target_zoom: f32,
+    /// How much the view is shaking, from 0 to 1
+    trauma: f32,
+    shake_offset: Vec2,
+}
+impl GameCamera {
+    /// How far the view moves at full trauma
+    pub const MAX_SHAKE_OFFSET: f32 = 12.0;
+    /// How much trauma wears off every frame
+    pub const TRAUMA_DECAY: f32 = 0.03;
+    /// Trauma from the biggest asteroid breaking. Smaller asteroids cause less
+    pub const IMPACT_TRAUMA: f32 = 0.3;
+    pub const DEATH_TRAUMA: f32 = 0.8;
+    /// The zoom jumps to this when the player dies and settles back from there
+    pub const DEATH_ZOOM: f32 = 1.15;
+    /// A following camera zooms out to this at the player's top speed
+    pub const TOP_SPEED_ZOOM: f32 = 0.75;
+    /// Fraction of the remaining distance to the target zoom covered every frame
+    pub const ZOOM_SMOOTHING: f32 = 0.05;
+    /// Fraction of the remaining distance to the player covered every frame
+    pub const FOLLOW_SMOOTHING: f32 = 0.1;
+}
+impl Default for GameCamera {
+    /// A fixed camera over the default arena
+    fn default() -> Self {
+        Self::fixed(&Arena::default())
+    }
+}
+impl GameCamera {
+    /// Shows the whole `arena`, centered
+    pub fn fixed(arena: &Arena) -> Self {
+        Self {
+            mode: CameraMode::Fixed,
+            view: arena.dimensions(),
+            center: arena.origin(),
+            zoom: 1.0,
+            target_zoom: 1.0,
+            trauma: 0.0,
+            shake_offset: Vec2::ZERO,
+        }
+    }
+    /// Shows `view` sized parts of the arena, starting at `center` and following the player from then on
+    pub fn following(view: Vec2, center: Vec2) -> Self {
+        Self {
+            mode: CameraMode::Follow,
+            view,
+            center,
+            ..Self::default()
+        }
+    }
+    pub fn mode(&self) -> CameraMode {
+        self.mode
+    }
+    pub fn view(&self) -> Vec2 {
+        self.view
+    }
+    pub fn center(&self) -> Vec2 {
+        self.center
+    }
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+    /// Scales the view up or down to fit the window, with black bars where the shapes don't match. Needs a window
+    pub fn screen_camera(&self) -> Camera2D {
+        let window = vec2(screen_width(), screen_height());
+        let scale = (window / self.view).min_element();
+        let viewport_size = self.view * scale;
+        let viewport_corner = (window - viewport_size) / 2.0;
+
+        Camera2D {
+            target: self.view / 2.0,
+            // macroquad flips y when drawing to the screen, so this keeps y pointing down
+            zoom: 2.0 / self.view,
+            viewport: Some((
+                viewport_corner.x as i32,
+                viewport_corner.y as i32,
+                viewport_size.x as i32,
+                viewport_size.y as i32,
+            )),
+            ..Default::default()
+        }
+    }
+    /// Moves a point in the arena to where it is in the view
+    pub fn arena_to_view(&self, point: Vec2) -> Vec2 {
+        (point - self.center - self.shake_offset) * self.zoom + self.view / 2.0
+    }
+    /// Draws through this camera into `inner`
+    pub fn renderer<'a>(
+        &'a self,
+        inner: &'a mut dyn Renderer,
+        arena: &Arena,
+    ) -> CameraRenderer<'a> {
+        CameraRenderer {
+            inner,
+            camera: self,
+            arena: arena.dimensions(),
+        }
+    }
+}
+impl GameCamera {
+    /// Adds to the trauma, up to 1
+    pub fn shake(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.0);
+    }
+    /// Smoothly zooms to `zoom`
+    pub fn zoom_to(&mut self, zoom: f32) {
+        self.target_zoom = zoom;
+    }
+    /// Jumps straight to `zoom`, then eases back to the target zoom
+    pub fn punch_zoom(&mut self, zoom: f32) {
+        self.zoom = zoom;
+    }
+    /// Move one time step further. `focus` is what a following camera follows
+    pub fn step(&mut self, focus: Vec2, arena: &Arena) {
+        self.trauma = (self.trauma - Self::TRAUMA_DECAY).max(0.0);
+        // squaring makes small amounts of trauma barely noticeable and big ones violent
+        let shake = Self::MAX_SHAKE_OFFSET * self.trauma * self.trauma;
+        self.shake_offset = polar_vec2(shake, gen_range(0.0, TAU));
+
+        self.zoom += (self.target_zoom - self.zoom) * Self::ZOOM_SMOOTHING;
+
+        match self.mode {
+            CameraMode::Fixed => self.center = arena.origin(),
+            CameraMode::Follow => {
+                // go the short way around when the player wraps
+                let delta = toroidal_delta(self.center, focus, arena.dimensions());
+                self.center += delta * Self::FOLLOW_SMOOTHING;
+                self.center = self.center.rem_euclid(arena.dimensions());
+            }
+        }
+    }
+}
+
+/// Moves, shakes and zooms everything drawn into `inner` by a [GameCamera].
+/// A following camera draws every shape at the copy closest to the view, since the view can be anywhere in the wrapping arena
+pub struct CameraRenderer<'a> {
+    inner: &'a mut dyn Renderer,
+    camera: &'a GameCamera,
+    arena: Vec2,
+}
+impl CameraRenderer<'_> {
+    /// Which copy of something at `point` to draw
+    fn wrap_shift(&self, point: Vec2) -> Vec2 {
+        match self.camera.mode {
+            CameraMode::Fixed => Vec2::ZERO,
+            CameraMode::Follow => {
+                let center = self.camera.center;
+                center + toroidal_delta(center, point, self.arena) - point
+            }
+        }
+    }
+    fn transform_all(&self, vertices: &[Vec2]) -> Vec<Vec2> {
+        let shift = vertices
+            .first()
+            .map_or(Vec2::ZERO, |&first| self.wrap_shift(first));
+        vertices
+            .iter()
+            .map(|&vertex| self.camera.arena_to_view(vertex + shift))
+            .collect()
+    }
+    fn transform(&self, point: Vec2) -> Vec2 {
+        self.camera.arena_to_view(point + self.wrap_shift(point))
+    }
+}
+impl Renderer for CameraRenderer<'_> {
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color) {
+        let vertices = self.transform_all(vertices);
+        self.inner.draw_polygon(&vertices, color);
+    }
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color) {
+        let vertices = self.transform_all(vertices);
+        self.inner.draw_polygon_lines(&vertices, thickness, color);
+    }
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let center = self.transform(center);
+        self.inner
+            .draw_circle(center, radius * self.camera.zoom, color);
+    }
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32) {
+        let center = self.transform(center);
+        self.inner
+            .draw_sprite(sprite, center, size * self.camera.zoom, rotation);
+    }
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        let position = self.transform(position);
+        self.inner
+            .draw_text(text, position, font_size * self.camera.zoom, color);
+    }
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        self.inner.measure_text(text, font_size * self.camera.zoom) / self.camera.zoom
+    }
+}
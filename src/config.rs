@@ -0,0 +1,238 @@
+//! Gameplay numbers that can be tuned without recompiling
+//! - read from [GameConfig::PATH] in the working directory, and read again whenever it changes by [crate::hot_reload]. Native only, the browser always uses the defaults
+//! - the defaults are the constants on [Player], [Bullet], [Asteroid] and [Kinematic], so the game plays the same without a file
+//! - every field is optional in the file, anything left out keeps its default. Misspelled keys are rejected instead of ignored
+//! - single values can also be read and changed by their `section.key` name, like `player.thrust`
+//! - values outside of what the game can play with are rejected, see [GameConfig::validate]
+
+use crate::{Asteroid, Bullet, Kinematic, Player};
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GameConfig {
+    pub player: PlayerConfig,
+    pub bullet: BulletConfig,
+    pub asteroid: AsteroidConfig,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerConfig {
+    pub thrust: f32,
+    pub max_speed: f32,
+    /// An angle in radians
+    pub rotation_delta: f32,
+    /// Fraction of the velocity and acceleration lost every frame
+    pub friction: f32,
+}
+impl Default for PlayerConfig {
+    fn default() -> Self {
+        Self {
+            thrust: Player::THRUST,
+            max_speed: Player::MAX_SPEED,
+            rotation_delta: Player::ROTATION_DELTA,
+            friction: Kinematic::FRICTION,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletConfig {
+    /// On top of the player's speed
+    pub speed: f32,
+    pub frames_alive: usize,
+}
+impl Default for BulletConfig {
+    fn default() -> Self {
+        Self {
+            speed: Bullet::SPEED,
+            frames_alive: Bullet::FRAMES_ALIVE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AsteroidConfig {
+    /// How much smaller each of the two children is than the asteroid that split
+    pub child_size_factor: f32,
+}
+impl Default for AsteroidConfig {
+    fn default() -> Self {
+        Self {
+            child_size_factor: Asteroid::CHILD_SIZE_FACTOR,
+        }
+    }
+}
+
+impl GameConfig {
+    pub const PATH: &'static str = "config.toml";
+}
+impl GameConfig {
+    pub fn from_toml(toml: &str) -> Result<Self, toml::de::Error> {
+        let config: Self = toml::from_str(toml)?;
+        config
+            .validate()
+            .map_err(<toml::de::Error as serde::de::Error>::custom)?;
+        Ok(config)
+    }
+    /// Checks every value is in its range. Speeds and sizes have to be positive,
+    /// and children have to be smaller than their asteroid or splitting never ends
+    pub fn validate(&self) -> Result<(), String> {
+        let is_positive = |value: f32| value > 0.0 && value.is_finite();
+        let checks = [
+            (
+                "player.thrust",
+                is_positive(self.player.thrust),
+                "more than 0",
+            ),
+            (
+                "player.max_speed",
+                is_positive(self.player.max_speed),
+                "more than 0",
+            ),
+            (
+                "player.rotation_delta",
+                is_positive(self.player.rotation_delta),
+                "more than 0",
+            ),
+            (
+                "player.friction",
+                (0.0..1.0).contains(&self.player.friction),
+                "at least 0 and less than 1",
+            ),
+            (
+                "bullet.speed",
+                is_positive(self.bullet.speed),
+                "more than 0",
+            ),
+            (
+                "bullet.frames_alive",
+                self.bullet.frames_alive > 0,
+                "more than 0",
+            ),
+            (
+                "asteroid.child_size_factor",
+                is_positive(self.asteroid.child_size_factor)
+                    && self.asteroid.child_size_factor < 1.0,
+                "between 0 and 1",
+            ),
+        ];
+        match checks.into_iter().find(|(_, is_valid, _)| !is_valid) {
+            Some((name, _, range)) => Err(format!("{name} has to be {range}")),
+            None => Ok(()),
+        }
+    }
+    /// Every `section.key` name with its value, sorted by name
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = Vec::new();
+        for (section, fields) in self.to_table() {
+            for (key, value) in fields.as_table().into_iter().flatten() {
+                entries.push((format!("{section}.{key}"), display_value(value)));
+            }
+        }
+        entries
+    }
+    /// The value named `section.key`
+    pub fn get(&self, name: &str) -> Result<String, String> {
+        let (section, key) = split_name(name)?;
+        self.to_table()
+            .get(section)
+            .and_then(|fields| fields.get(key))
+            .map(display_value)
+            .ok_or_else(|| format!("there is no {name} in the config"))
+    }
+    /// Parses `value` the same way as in the file and changes the value named `section.key` to it
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let (section, key) = split_name(name)?;
+        let mut table = self.to_table();
+        let field = table
+            .get_mut(section)
+            .and_then(|fields| fields.get_mut(key))
+            .ok_or_else(|| format!("there is no {name} in the config"))?;
+        let mut parsed: Table = toml::from_str(&format!("value = {value}"))
+            .map_err(|_| format!("{value} is not a valid value"))?;
+        *field = parsed.remove("value").expect("was just parsed");
+
+        let config: Self = table
+            .try_into()
+            .map_err(|error: toml::de::Error| format!("{name}: {}", error.message()))?;
+        config.validate()?;
+        *self = config;
+        Ok(())
+    }
+    /// The config in [Self::PATH], or the defaults if there is no such file
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load() -> Result<Self, toml::de::Error> {
+        match std::fs::read_to_string(Self::PATH) {
+            Ok(toml) => Self::from_toml(&toml),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
+impl GameConfig {
+    fn to_table(&self) -> Table {
+        Table::try_from(self).expect("the config only has numbers in it")
+    }
+}
+fn split_name(name: &str) -> Result<(&str, &str), String> {
+    name.split_once('.')
+        .ok_or_else(|| format!("{name} should look like section.key"))
+}
+/// The config is all `f32` so floats are shown as one, without the noise from widening them
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Float(float) => (*float as f32).to_string(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert_eq!(GameConfig::default().validate(), Ok(()));
+        assert_eq!(GameConfig::from_toml(""), Ok(GameConfig::default()));
+    }
+
+    #[test]
+    fn committed_file_lists_the_defaults() {
+        let toml = include_str!("../config.toml");
+        assert_eq!(GameConfig::from_toml(toml), Ok(GameConfig::default()));
+    }
+
+    #[test]
+    fn missing_keys_keep_their_defaults() {
+        let config = GameConfig::from_toml("[player]\nthrust = 1.0").unwrap();
+        assert_eq!(config.player.thrust, 1.0);
+        assert_eq!(config.player.max_speed, Player::MAX_SPEED);
+        assert_eq!(config.bullet, BulletConfig::default());
+    }
+
+    #[test]
+    fn misspelled_key_is_rejected() {
+        assert!(GameConfig::from_toml("[player]\nthrst = 1.0").is_err());
+        assert!(GameConfig::from_toml("[bullets]\nspeed = 1.0").is_err());
+    }
+
+    #[test]
+    fn value_out_of_range_is_rejected() {
+        assert!(GameConfig::from_toml("[asteroid]\nchild_size_factor = 1.0").is_err());
+        let mut config = GameConfig::default();
+        assert!(config.set("player.friction", "1.0").is_err());
+        assert_eq!(config, GameConfig::default());
+    }
+
+    #[test]
+    fn set_changes_one_value() {
+        let mut config = GameConfig::default();
+        config.set("bullet.frames_alive", "40").unwrap();
+        assert_eq!(config.get("bullet.frames_alive"), Ok(String::from("40")));
+        assert!(config.set("bullet.frames", "40").is_err());
+    }
+}
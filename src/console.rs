@@ -0,0 +1,174 @@
+//! A developer console drawn over the game, opened and closed with [Console::TOGGLE_KEY]
+//! - the game doesn't step or take any other input while it's open
+//! - a typed line is parsed into a [Command], which the [crate::Game] runs
+//! - `help` lists every command, and what they print shows up in the console's log
+
+use crate::Renderer;
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// `count` random asteroids, all at `position` if there is one
+    Spawn {
+        count: usize,
+        position: Option<Vec2>,
+    },
+    Lives(usize),
+    /// Switch the player not colliding with asteroids on or off
+    GodMode,
+    /// Show the whole config, one value, or change one value
+    Config {
+        key: Option<String>,
+        value: Option<String>,
+    },
+    Seed(u64),
+    /// Step the world this many frames, even while paused
+    Step(usize),
+    /// See [crate::TimeControl]
+    TimeScale(f32),
+    /// Switch the [crate::Autopilot] flying the ship on or off
+    Autopilot,
+    Help,
+}
+impl Command {
+    pub const HELP: [&'static str; 9] = [
+        "spawn <count> [<x> <y>]",
+        "lives <count>",
+        "god",
+        "config [<section.key> [<value>]]",
+        "seed <number>",
+        "step [<frames>]",
+        "speed <time scale>",
+        "autopilot",
+        "help",
+    ];
+}
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut words = line.split_whitespace();
+        let name = words.next().ok_or("type help for a list of commands")?;
+        let arguments: Vec<&str> = words.collect();
+        let command = match (name, arguments.as_slice()) {
+            ("spawn", [count]) => Self::Spawn {
+                count: parse_argument(count)?,
+                position: None,
+            },
+            ("spawn", [count, x, y]) => Self::Spawn {
+                count: parse_argument(count)?,
+                position: Some(vec2(parse_argument(x)?, parse_argument(y)?)),
+            },
+            ("lives", [count]) => Self::Lives(parse_argument(count)?),
+            ("god", []) => Self::GodMode,
+            ("config", []) => Self::Config {
+                key: None,
+                value: None,
+            },
+            ("config", [key]) => Self::Config {
+                key: Some(key.to_string()),
+                value: None,
+            },
+            ("config", [key, value]) => Self::Config {
+                key: Some(key.to_string()),
+                value: Some(value.to_string()),
+            },
+            ("seed", [seed]) => Self::Seed(parse_argument(seed)?),
+            ("step", []) => Self::Step(1),
+            ("step", [frames]) => Self::Step(parse_argument(frames)?),
+            ("speed", [scale]) => {
+                let scale: f32 = parse_argument(scale)?;
+                if !scale.is_finite() {
+                    return Err(format!("{scale} is not a valid time scale"));
+                }
+                Self::TimeScale(scale)
+            }
+            ("autopilot", []) => Self::Autopilot,
+            ("help", []) => Self::Help,
+            _ => {
+                let usage = Self::HELP
+                    .iter()
+                    .find(|usage| usage.split(' ').next() == Some(name));
+                return Err(match usage {
+                    Some(usage) => format!("usage: {usage}"),
+                    None => format!("unknown command {name}, type help for a list of commands"),
+                });
+            }
+        };
+        Ok(command)
+    }
+}
+fn parse_argument<T: std::str::FromStr>(argument: &str) -> Result<T, String> {
+    argument
+        .parse()
+        .map_err(|_| format!("{argument} is not a valid number"))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Console {
+    is_open: bool,
+    input: String,
+    /// Entered lines and what they printed, oldest first
+    log: Vec<String>,
+}
+impl Console {
+    /// The key left of 1, with ` and ~ on it
+    pub const TOGGLE_KEY: KeyCode = KeyCode::GraveAccent;
+    /// How many of the latest log lines are shown
+    pub const VISIBLE_LINES: usize = 10;
+    pub const FONT_SIZE: f32 = 20.0;
+}
+impl Console {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+    pub fn toggle(&mut self) {
+        self.is_open = !self.is_open;
+        // the toggle key would otherwise be typed
+        clear_input_queue();
+    }
+    pub fn log(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+    }
+    /// Type characters, BACKSPACE to erase, ENTER to submit and ESCAPE to close. Returns the submitted line
+    pub fn handle_input(&mut self) -> Option<String> {
+        if is_key_pressed(KeyCode::Escape) {
+            self.toggle();
+            return None;
+        }
+        while let Some(character) = get_char_pressed() {
+            if !character.is_control() && character != '`' && character != '~' {
+                self.input.push(character);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.input.pop();
+        }
+        if !is_key_pressed(KeyCode::Enter) {
+            return None;
+        }
+        let line = std::mem::take(&mut self.input);
+        self.log(format!("> {line}"));
+        Some(line)
+    }
+    /// The log and the line being typed in a box across the top of a `width` wide view
+    pub fn draw(&self, renderer: &mut dyn Renderer, width: f32) {
+        const MARGIN: f32 = 8.0;
+        let height = (Self::VISIBLE_LINES + 1) as f32 * Self::FONT_SIZE + MARGIN * 2.0;
+        let background = [
+            vec2(0.0, 0.0),
+            vec2(width, 0.0),
+            vec2(width, height),
+            vec2(0.0, height),
+        ];
+        renderer.draw_polygon(&background, Color::new(0.0, 0.0, 0.0, 0.8));
+
+        let visible = self.log.len().saturating_sub(Self::VISIBLE_LINES);
+        let mut position = vec2(MARGIN, MARGIN);
+        for line in &self.log[visible..] {
+            position.y += Self::FONT_SIZE;
+            renderer.draw_text(line, position, Self::FONT_SIZE, LIGHTGRAY);
+        }
+        position.y = height - MARGIN;
+        let prompt = format!("> {}_", self.input);
+        renderer.draw_text(&prompt, position, Self::FONT_SIZE, YELLOW);
+    }
+}
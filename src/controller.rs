@@ -0,0 +1,210 @@
+//! What the ship should do each step, decided by a person or by the computer
+//! - every [Controller] turns the [World] as it is into [Actions], and the world only ever sees the actions
+//! - [Keyboard]: the arrow keys and Z
+//! - [Autopilot]: shoots down the most threatening asteroid and gets out of the way of anything about to hit the ship.
+//!   It plays the demo on the title screen, and can take over the game from the console
+
+use crate::{toroidal_delta, Asteroid, KinematicGetters, Player, World};
+use macroquad::prelude::*;
+use std::f32::consts::{PI, TAU};
+
+/// Everything the ship can do in one step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Actions {
+    pub rotate_left: bool,
+    pub rotate_right: bool,
+    pub thrust: bool,
+    pub fire: bool,
+}
+
+impl Actions {
+    /// How many different [Actions] there are, for agents that pick one of a fixed set
+    pub const COUNT: usize = 16;
+
+    /// Every combination has its own index from 0 to [Self::COUNT], one bit for each action in the order they are declared
+    pub fn from_index(index: usize) -> Self {
+        Self {
+            rotate_left: index & 1 != 0,
+            rotate_right: index & 2 != 0,
+            thrust: index & 4 != 0,
+            fire: index & 8 != 0,
+        }
+    }
+}
+
+pub trait Controller {
+    fn actions(&mut self, world: &World) -> Actions;
+}
+
+/// - LEFT and RIGHT rotate
+/// - UP thrusts
+/// - Z fires once per press
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Keyboard;
+impl Controller for Keyboard {
+    fn actions(&mut self, _world: &World) -> Actions {
+        Actions {
+            rotate_left: is_key_down(KeyCode::Left),
+            rotate_right: is_key_down(KeyCode::Right),
+            thrust: is_key_down(KeyCode::Up),
+            fire: is_key_pressed(KeyCode::Z),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Autopilot {
+    frames_until_fire: usize,
+}
+impl Autopilot {
+    /// Steps between shots, so one asteroid doesn't get a wall of bullets
+    pub const FIRE_COOLDOWN_FRAMES: usize = 10;
+    /// Fires when the aim is off by less than this, in radians
+    pub const AIM_TOLERANCE: f32 = 0.05;
+    /// An asteroid that will pass closer than this to the edge of the ship gets avoided
+    pub const EVADE_DISTANCE: f32 = Player::SIZE * 2.0;
+    /// ...if it gets there within this many steps
+    pub const EVADE_FRAMES: f32 = 45.0;
+    /// Only thrusts while facing within this angle of the way out, in radians
+    pub const EVADE_THRUST_ANGLE: f32 = PI / 3.0;
+}
+impl Controller for Autopilot {
+    fn actions(&mut self, world: &World) -> Actions {
+        self.frames_until_fire = self.frames_until_fire.saturating_sub(1);
+        let player = world.player();
+        let approaches: Vec<Approach> = world
+            .asteroids()
+            .iter()
+            .map(|asteroid| Approach::new(player, asteroid, world))
+            .collect();
+
+        // getting out of the way comes before shooting
+        let imminent = approaches
+            .iter()
+            .filter(|approach| approach.is_imminent())
+            .min_by(|a, b| a.frames.total_cmp(&b.frames));
+        if let Some(approach) = imminent {
+            // away from where it will be closest, or straight away if it's heading right at the ship
+            let away = -(approach.closest.try_normalize())
+                .or(approach.delta.try_normalize())
+                .unwrap_or(Vec2::X);
+            let angle_off = angle_between(player.orientation(), away.to_angle());
+            return Actions {
+                thrust: angle_off.abs() < Self::EVADE_THRUST_ANGLE,
+                ..Self::turn_towards(angle_off, world)
+            };
+        }
+
+        let target = approaches
+            .iter()
+            .min_by(|a, b| a.threat().total_cmp(&b.threat()));
+        let Some(target) = target else {
+            return Actions::default();
+        };
+        let config = world.config();
+        let bullet_speed = config.bullet.speed + player.speed();
+        let aim = intercept(target.delta, target.asteroid_velocity, bullet_speed);
+        let angle_off = angle_between(player.orientation(), aim.to_angle());
+
+        let bullet_range = bullet_speed * config.bullet.frames_alive as f32;
+        let fire = angle_off.abs() < Self::AIM_TOLERANCE
+            && aim.length() < bullet_range
+            && self.frames_until_fire == 0;
+        if fire {
+            self.frames_until_fire = Self::FIRE_COOLDOWN_FRAMES;
+        }
+        Actions {
+            fire,
+            ..Self::turn_towards(angle_off, world)
+        }
+    }
+}
+impl Autopilot {
+    /// Rotates to make `angle_off` smaller, unless a rotation would overshoot
+    fn turn_towards(angle_off: f32, world: &World) -> Actions {
+        let rotation_delta = world.config().player.rotation_delta;
+        Actions {
+            rotate_left: angle_off < -rotation_delta / 2.0,
+            rotate_right: angle_off > rotation_delta / 2.0,
+            ..Actions::default()
+        }
+    }
+}
+
+/// How an asteroid will pass by the player if neither changes course
+struct Approach {
+    /// From the player to the asteroid, the short way around the arena
+    delta: Vec2,
+    /// Not relative to the player, since bullets don't take on the player's velocity
+    asteroid_velocity: Vec2,
+    /// Steps until it is closest, never negative
+    frames: f32,
+    /// From the player to the asteroid when it is closest
+    closest: Vec2,
+    /// Between the edges of the two when it is closest
+    gap: f32,
+}
+impl Approach {
+    fn new(player: &Player, asteroid: &Asteroid, world: &World) -> Self {
+        let delta = toroidal_delta(
+            player.position(),
+            asteroid.position(),
+            world.arena().dimensions(),
+        );
+        let velocity = asteroid.velocity() - player.velocity();
+        let speed_squared = velocity.length_squared();
+        let frames = if speed_squared > 0.0 {
+            (-delta.dot(velocity) / speed_squared).max(0.0)
+        } else {
+            0.0
+        };
+        let closest = delta + velocity * frames;
+        Self {
+            delta,
+            asteroid_velocity: asteroid.velocity(),
+            frames,
+            closest,
+            gap: closest.length() - asteroid.size() - Player::SIZE,
+        }
+    }
+    fn is_imminent(&self) -> bool {
+        self.gap < Autopilot::EVADE_DISTANCE && self.frames < Autopilot::EVADE_FRAMES
+    }
+    /// Lower is more threatening. Every step of warning counts as much as being a ship length further away
+    fn threat(&self) -> f32 {
+        self.gap.max(0.0) + self.frames * Player::SIZE
+    }
+}
+
+/// Where to aim for something at `delta` moving at `velocity` to be hit by a bullet at `speed`.
+/// Straight at it if the bullet can never catch up
+fn intercept(delta: Vec2, velocity: Vec2, speed: f32) -> Vec2 {
+    // |delta + velocity * t| = speed * t, as a quadratic in t
+    let a = velocity.length_squared() - speed * speed;
+    let b = 2.0 * delta.dot(velocity);
+    let c = delta.length_squared();
+    let time = if a.abs() < f32::EPSILON {
+        -c / b
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return delta;
+        }
+        let root = discriminant.sqrt();
+        let times = [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)];
+        times
+            .into_iter()
+            .filter(|&time| time > 0.0)
+            .fold(f32::INFINITY, f32::min)
+    };
+    if time.is_finite() && time > 0.0 {
+        delta + velocity * time
+    } else {
+        delta
+    }
+}
+
+/// How far `to` is clockwise from `from`, between -PI and PI
+fn angle_between(from: f32, to: f32) -> f32 {
+    (to - from + PI).rem_euclid(TAU) - PI
+}
@@ -0,0 +1,104 @@
+//! Draws what the simulation sees on top of the [World], to find out why something did or didn't collide
+//! - green: the collision polygons from `vertices()`, and the broad-phase grid cells with asteroids in them
+//! - sky blue: where everything will be in [World::DEBUG_VELOCITY_FRAMES] frames at its current velocity
+//! - magenta: the player's acceleration
+//! - yellow: the points checked with [crate::is_point_in_polygon]. The player's vertices, and the path each bullet took
+//!   since the last step, labelled with the frames it has left
+
+use crate::{Entity, KinematicGetters, Renderer, World};
+use macroquad::prelude::*;
+
+impl World {
+    /// How far ahead the velocity lines reach
+    pub const DEBUG_VELOCITY_FRAMES: f32 = 8.0;
+    /// The acceleration is tiny next to the velocity, so it's drawn this many times longer
+    pub const DEBUG_ACCELERATION_SCALE: f32 = 200.0;
+}
+impl World {
+    pub fn draw_debug(&self, renderer: &mut dyn Renderer) {
+        self.draw_in_arena(renderer, |renderer| {
+            self.draw_debug_grid(renderer);
+            self.draw_debug_colliders(renderer);
+            self.draw_debug_motion(renderer);
+            self.draw_debug_checked_points(renderer);
+        });
+    }
+}
+impl World {
+    fn draw_debug_grid(&self, renderer: &mut dyn Renderer) {
+        let grid = self.asteroid_grid();
+        let size = grid.cell_size();
+        for (corner, count) in grid.occupied_cells() {
+            let cell = [
+                corner,
+                corner + vec2(size.x, 0.0),
+                corner + size,
+                corner + vec2(0.0, size.y),
+            ];
+            renderer.draw_polygon(&cell, Color::new(0.0, 0.9, 0.2, 0.08));
+            renderer.draw_polygon_lines(&cell, 1.0, DARKGREEN);
+            renderer.draw_text(&count.to_string(), corner + 14.0, 14.0, DARKGREEN);
+        }
+    }
+    fn draw_debug_colliders(&self, renderer: &mut dyn Renderer) {
+        for asteroid in self.asteroids() {
+            renderer.draw_polygon_lines(&asteroid.vertices(), 1.0, GREEN);
+        }
+        renderer.draw_polygon_lines(&self.player().vertices(), 1.0, GREEN);
+    }
+    fn draw_debug_motion(&self, renderer: &mut dyn Renderer) {
+        let positions_and_velocities = self
+            .asteroids()
+            .iter()
+            .map(|asteroid| (asteroid.position(), asteroid.velocity()))
+            .chain(
+                self.bullets()
+                    .iter()
+                    .map(|bullet| (bullet.position(), bullet.velocity())),
+            )
+            .chain([(self.player().position(), self.player().velocity())]);
+        for (position, velocity) in positions_and_velocities {
+            draw_arrow(
+                renderer,
+                position,
+                velocity * Self::DEBUG_VELOCITY_FRAMES,
+                SKYBLUE,
+            );
+        }
+
+        let player = self.player();
+        let acceleration = player.acceleration() * Self::DEBUG_ACCELERATION_SCALE;
+        draw_arrow(renderer, player.position(), acceleration, MAGENTA);
+    }
+    fn draw_debug_checked_points(&self, renderer: &mut dyn Renderer) {
+        for vertex in self.player().vertices() {
+            renderer.draw_circle(vertex, 3.0, YELLOW);
+        }
+        for bullet in self.bullets() {
+            let position = bullet.position();
+            draw_line(renderer, position - bullet.motion(), position, YELLOW);
+            renderer.draw_circle(position, 3.0, YELLOW);
+            if let Some(lifetime) = bullet.lifetime() {
+                let label = lifetime.frames_left().to_string();
+                renderer.draw_text(&label, position + vec2(6.0, -6.0), 14.0, YELLOW);
+            }
+        }
+    }
+}
+
+/// A polygon with two vertices is a line, drawn there and back
+fn draw_line(renderer: &mut dyn Renderer, start: Vec2, end: Vec2, color: Color) {
+    renderer.draw_polygon_lines(&[start, end], 1.0, color);
+}
+/// A line from `start` along `vector` with a head at the end, unless it's too short to see
+fn draw_arrow(renderer: &mut dyn Renderer, start: Vec2, vector: Vec2, color: Color) {
+    const HEAD_SIZE: f32 = 6.0;
+    if vector.length() < 1.0 {
+        return;
+    }
+    let end = start + vector;
+    let back = -vector.normalize() * HEAD_SIZE;
+    let side = back.perp() / 2.0;
+    draw_line(renderer, start, end, color);
+    renderer.draw_polygon_lines(&[end, end + back + side, end + back - side], 1.0, color);
+}
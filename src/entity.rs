@@ -0,0 +1,123 @@
+//! Components shared by everything that moves around the arena, so a new kind of thing only has to say what's different about it
+//! - [Kinematic]: position, velocity and acceleration
+//! - [Collider]: how far it reaches for the broad-phase, and whether it was hit
+//! - [Lifetime]: how many more steps it has, for things that disappear on their own
+//! - anything with [Components] implements [Entity], and gets [KinematicGetters], [KinematicMutators], collisions and being alive for free
+
+use crate::{Arena, Kinematic, KinematicGetters, KinematicMutators};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Collider {
+    /// Nothing of the entity is further than this from its position
+    radius: f32,
+    has_collided: bool,
+}
+impl Collider {
+    pub fn new(radius: f32) -> Self {
+        Self {
+            radius,
+            has_collided: false,
+        }
+    }
+    pub fn radius(&self) -> f32 {
+        self.radius
+    }
+    pub fn has_collided(&self) -> bool {
+        self.has_collided
+    }
+    pub fn set_collided(&mut self) {
+        self.has_collided = true;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lifetime {
+    frames: usize,
+    frames_left: usize,
+}
+impl Lifetime {
+    pub fn new(frames: usize) -> Self {
+        Self {
+            frames,
+            frames_left: frames,
+        }
+    }
+    pub fn frames_left(&self) -> usize {
+        self.frames_left
+    }
+    /// Hasn't been stepped yet
+    pub fn is_new(&self) -> bool {
+        self.frames_left == self.frames
+    }
+    pub fn is_over(&self) -> bool {
+        self.frames_left == 0
+    }
+    pub fn step(&mut self) {
+        self.frames_left = self.frames_left.saturating_sub(1);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Components {
+    pub kinematic: Kinematic,
+    pub collider: Collider,
+    /// [None] lives until it collides
+    pub lifetime: Option<Lifetime>,
+}
+impl Components {
+    pub fn new(kinematic: Kinematic, collider: Collider) -> Self {
+        Self {
+            kinematic,
+            collider,
+            lifetime: None,
+        }
+    }
+    pub fn with_lifetime(self, lifetime: Lifetime) -> Self {
+        Self {
+            lifetime: Some(lifetime),
+            ..self
+        }
+    }
+}
+
+pub trait Entity {
+    fn components(&self) -> &Components;
+    fn components_mut(&mut self) -> &mut Components;
+
+    fn collider(&self) -> &Collider {
+        &self.components().collider
+    }
+    fn lifetime(&self) -> Option<&Lifetime> {
+        self.components().lifetime.as_ref()
+    }
+    fn has_collided(&self) -> bool {
+        self.collider().has_collided()
+    }
+    fn set_collided(&mut self) {
+        self.components_mut().collider.set_collided();
+    }
+    /// Until it collides or its lifetime is over
+    fn is_alive(&self) -> bool {
+        !self.has_collided() && !self.lifetime().is_some_and(Lifetime::is_over)
+    }
+    /// Moves, wraps around the arena and gets older
+    fn step_components(&mut self, arena: &Arena) {
+        let components = self.components_mut();
+        components.kinematic.keep_in_arena(arena);
+        components.kinematic.step_motion();
+        if let Some(lifetime) = &mut components.lifetime {
+            lifetime.step();
+        }
+    }
+}
+impl<T: Entity> KinematicGetters for T {
+    fn kinematic(&self) -> &Kinematic {
+        &self.components().kinematic
+    }
+}
+impl<T: Entity> KinematicMutators for T {
+    fn kinematic_mut(&mut self) -> &mut Kinematic {
+        &mut self.components_mut().kinematic
+    }
+}
@@ -0,0 +1,159 @@
+//! The game as a reinforcement learning environment, in the style of OpenAI Gym
+//! - [Environment::reset] starts a new game from a seed, [Environment::step] plays one step of [Actions] and says how it went
+//! - no window is needed, so thousands of steps run every second
+//! - the observation is a vector of numbers around -1 to 1: the ship, then the nearest asteroids relative to it.
+//!   See [Environment::observation] for the layout
+//! - a picture of the world can be drawn on the cpu with [Environment::render] for agents that learn from pixels
+//! - the random numbers are shared by everything in the process, so an episode only repeats exactly if nothing else uses them meanwhile
+
+use crate::{
+    toroidal_delta, Actions, Arena, Asteroid, Draw, Entity, GameConfig, GameEvent,
+    KinematicGetters, Player, SoftwareRenderer, World,
+};
+use macroquad::{prelude::*, rand};
+
+pub struct Environment {
+    arena: Arena,
+    config: GameConfig,
+    world: World,
+    /// How many asteroids are in the observation
+    nearest_asteroids: usize,
+    /// The episode is cut off after this many steps, if there is a limit
+    max_steps: Option<usize>,
+    steps: usize,
+    /// Only created the first time [Self::render] is called
+    renderer: Option<SoftwareRenderer>,
+}
+impl Environment {
+    pub const DEFAULT_NEAREST_ASTEROIDS: usize = 8;
+    /// Numbers in the observation for the ship: position, velocity, orientation, invulnerability and lives
+    pub const SHIP_FEATURES: usize = 8;
+    /// Numbers in the observation for each asteroid: whether it's there, relative position, relative velocity and size
+    pub const ASTEROID_FEATURES: usize = 6;
+    /// Taken off the reward for every life lost
+    pub const DEATH_PENALTY: f32 = 1000.0;
+}
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new(Arena::default())
+    }
+}
+impl Environment {
+    /// Ready to be [Self::reset]
+    pub fn new(arena: Arena) -> Self {
+        Self {
+            arena,
+            config: GameConfig::default(),
+            world: World::new(arena),
+            nearest_asteroids: Self::DEFAULT_NEAREST_ASTEROIDS,
+            max_steps: None,
+            steps: 0,
+            renderer: None,
+        }
+    }
+    pub fn with_nearest_asteroids(self, nearest_asteroids: usize) -> Self {
+        Self {
+            nearest_asteroids,
+            ..self
+        }
+    }
+    pub fn with_max_steps(self, max_steps: usize) -> Self {
+        Self {
+            max_steps: Some(max_steps),
+            ..self
+        }
+    }
+    pub fn with_config(self, config: GameConfig) -> Self {
+        Self { config, ..self }
+    }
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+    /// How long [Self::observation] is
+    pub fn observation_len(&self) -> usize {
+        Self::SHIP_FEATURES + self.nearest_asteroids * Self::ASTEROID_FEATURES
+    }
+    /// Starts a new game, the same one every time for the same `seed`. Returns the first observation
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        rand::srand(seed);
+        self.world = World::new(self.arena);
+        self.world.set_config(self.config.clone());
+        self.steps = 0;
+        self.observation()
+    }
+    /// Plays one step. Returns the observation after it, the reward for it and whether the episode is done.
+    /// The reward is the points scored, minus [Self::DEATH_PENALTY] for every life lost
+    pub fn step(&mut self, actions: Actions) -> (Vec<f32>, f32, bool) {
+        self.world.apply_actions(actions);
+        self.world.step();
+        self.steps += 1;
+
+        let reward = self
+            .world
+            .take_events()
+            .iter()
+            .map(|event| match event {
+                GameEvent::AsteroidDestroyed { points, .. } => *points as f32,
+                GameEvent::PlayerDied { .. } => -Self::DEATH_PENALTY,
+                _ => 0.0,
+            })
+            .sum();
+        let is_cut_off = self
+            .max_steps
+            .is_some_and(|max_steps| self.steps >= max_steps);
+        let done = self.world.is_over() || is_cut_off;
+        (self.observation(), reward, done)
+    }
+    /// - ship: position in the arena from 0 to 1, velocity as a fraction of the max speed,
+    ///   the cosine and sine of its orientation, 1 if invulnerable and the lives left divided by the starting lives
+    /// - then for each of the nearest asteroids, closest first: 1 if there is one, position relative to the ship
+    ///   as a fraction of the arena, velocity relative to the ship as a fraction of the ship's max speed,
+    ///   and size as a fraction of [Asteroid::MAX_SIZE]. All zeros once there are no more asteroids
+    pub fn observation(&self) -> Vec<f32> {
+        let player = self.world.player();
+        let dimensions = self.arena.dimensions();
+        let max_speed = self.config.player.max_speed;
+
+        let mut observation = Vec::with_capacity(self.observation_len());
+        let position = player.position() / dimensions;
+        let velocity = player.velocity() / max_speed;
+        observation.extend([position.x, position.y, velocity.x, velocity.y]);
+        observation.extend([player.orientation().cos(), player.orientation().sin()]);
+        observation.push(if player.is_invulnerable() { 1.0 } else { 0.0 });
+        observation.push(player.lives() as f32 / Player::STARTING_LIVES as f32);
+
+        let mut asteroids: Vec<(Vec2, &Asteroid)> = self
+            .world
+            .asteroids()
+            .iter()
+            .filter(|asteroid| asteroid.is_alive())
+            .map(|asteroid| {
+                let delta = toroidal_delta(player.position(), asteroid.position(), dimensions);
+                (delta, asteroid)
+            })
+            .collect();
+        asteroids.sort_by(|(a, _), (b, _)| a.length_squared().total_cmp(&b.length_squared()));
+        for index in 0..self.nearest_asteroids {
+            match asteroids.get(index) {
+                Some((delta, asteroid)) => {
+                    let position = *delta / dimensions;
+                    let velocity = (asteroid.velocity() - player.velocity()) / max_speed;
+                    let size = asteroid.size() / Asteroid::MAX_SIZE;
+                    observation.extend([1.0, position.x, position.y, velocity.x, velocity.y, size]);
+                }
+                None => observation.extend([0.0; Self::ASTEROID_FEATURES]),
+            }
+        }
+        observation
+    }
+    /// Draws the world the way [crate::RenderMode::Vector] outlines it, without the glow, one pixel per arena unit
+    pub fn render(&mut self) -> &Image {
+        let dimensions = self.arena.dimensions();
+        let renderer = self
+            .renderer
+            .get_or_insert_with(|| SoftwareRenderer::new(dimensions.x as u16, dimensions.y as u16));
+        renderer.clear(BLACK);
+        self.world.draw_outline(renderer);
+        renderer.frame()
+    }
+}
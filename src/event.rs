@@ -0,0 +1,34 @@
+//! Things that happen in the [crate::World], so whatever cares about them doesn't have to be wired into the simulation
+//! - the world queues events while it steps, and reacts to some itself: scoring, extra lives and camera shake
+//! - the [crate::Game] takes the rest every frame for sound effects and the heartbeat music
+//! - pickups don't exist yet, so [GameEvent::PickupCollected] is never sent
+
+use macroquad::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GameEvent {
+    BulletFired {
+        position: Vec2,
+    },
+    /// An asteroid was hit and split
+    AsteroidDestroyed {
+        position: Vec2,
+        size: f32,
+        points: usize,
+    },
+    PlayerDied {
+        position: Vec2,
+        lives_left: usize,
+    },
+    ExtraLifeGained {
+        lives: usize,
+    },
+    /// Not sent until there are pickups to collect
+    PickupCollected {
+        position: Vec2,
+    },
+    /// Every asteroid of `wave` is gone. The next wave has already started
+    WaveCleared {
+        wave: usize,
+    },
+}
@@ -0,0 +1,586 @@
+//! Implement a state machine around the [World]
+//! - Title: wait for the player to start a new game or continue a saved one, with the [Autopilot] playing a silent demo behind
+//! - Playing: handle input and step the world, as many times per frame as the [TimeControl] says
+//! - Paused: keep drawing the world but don't step it. The world can be saved and volumes changed from here
+//! - EnterInitials: a new high score was set so ask for the player's initials
+//! - GameOver: show the final score until the player goes back to the title
+//! - the developer [Console] can be opened in any state, and freezes the game while it's open
+//! - the debug overlay from [crate::debug] can be drawn over the world while it's being played
+
+use crate::{
+    Actions, Arena, Autopilot, Command, Console, Controller, Draw, GameConfig, GameEvent,
+    Heartbeat, HighScoreTable, Keyboard, Mixer, RenderMode, Renderer, SoundEffect, TimeControl,
+    VectorDisplay, World,
+};
+use macroquad::prelude::*;
+use std::cell::OnceCell;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameState {
+    Title,
+    Playing,
+    Paused,
+    EnterInitials,
+    GameOver,
+}
+
+/// Which volume the arrow keys change on the pause screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum VolumeSetting {
+    Master,
+    Music,
+    Sfx,
+}
+impl VolumeSetting {
+    const ALL: [Self; 3] = [Self::Master, Self::Music, Self::Sfx];
+}
+
+pub struct Game {
+    state: GameState,
+    /// Every new world is this size
+    arena: Arena,
+    /// Every new world plays by this
+    config: GameConfig,
+    world: World,
+    high_scores: HighScoreTable,
+    initials: String,
+    /// Where the last game landed on the high score table
+    new_high_score_rank: Option<usize>,
+    has_saved_game: bool,
+    /// Shown on the pause screen after trying to save
+    save_message: Option<String>,
+    mixer: Mixer,
+    heartbeat: Heartbeat,
+    selected_volume: VolumeSetting,
+    render_mode: RenderMode,
+    /// Only created the first time [RenderMode::Vector] is drawn
+    vector_display: OnceCell<VectorDisplay>,
+    console: Console,
+    is_debug_overlay_on: bool,
+    time: TimeControl,
+    /// Flies the ship instead of the [Keyboard] if there is one
+    autopilot: Option<Autopilot>,
+    /// Fire was pressed but no step has happened since to fire the bullet
+    is_fire_latched: bool,
+    /// Played on the title screen
+    demo: World,
+    demo_pilot: Autopilot,
+}
+impl Game {
+    pub const START_KEY: KeyCode = KeyCode::Enter;
+    /// Starts a new game in a [World::new_large] instead
+    pub const LARGE_WORLD_KEY: KeyCode = KeyCode::L;
+    pub const PAUSE_KEYS: [KeyCode; 2] = [KeyCode::P, KeyCode::Escape];
+    pub const SAVE_KEY: KeyCode = KeyCode::S;
+    pub const CONTINUE_KEY: KeyCode = KeyCode::C;
+    pub const RENDER_MODE_KEY: KeyCode = KeyCode::V;
+    pub const DEBUG_OVERLAY_KEY: KeyCode = KeyCode::F3;
+    pub const SLOWER_KEY: KeyCode = KeyCode::Minus;
+    pub const FASTER_KEY: KeyCode = KeyCode::Equal;
+    pub const FREEZE_KEY: KeyCode = KeyCode::F5;
+    /// Takes one step while frozen
+    pub const ADVANCE_KEY: KeyCode = KeyCode::F6;
+}
+impl Default for Game {
+    /// A game without sound
+    fn default() -> Self {
+        Self::new(Mixer::silent())
+    }
+}
+impl Game {
+    pub fn new(mixer: Mixer) -> Self {
+        Self::with_arena(mixer, Arena::default())
+    }
+    pub fn with_arena(mixer: Mixer, arena: Arena) -> Self {
+        let world = World::new(arena);
+        let mut heartbeat = Heartbeat::default();
+        heartbeat.start_wave(world.asteroids());
+        Self {
+            state: GameState::Title,
+            arena,
+            config: GameConfig::default(),
+            world,
+            high_scores: HighScoreTable::load(),
+            initials: String::new(),
+            new_high_score_rank: None,
+            has_saved_game: World::has_save(),
+            save_message: None,
+            mixer,
+            heartbeat,
+            selected_volume: VolumeSetting::Master,
+            render_mode: RenderMode::default(),
+            vector_display: OnceCell::new(),
+            console: Console::default(),
+            is_debug_overlay_on: false,
+            time: TimeControl::default(),
+            autopilot: None,
+            is_fire_latched: false,
+            demo: World::new(arena),
+            demo_pilot: Autopilot::default(),
+        }
+    }
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+    /// Also changes the config of the world being played
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.world.set_config(config.clone());
+        self.demo.set_config(config.clone());
+        self.config = config;
+    }
+    pub fn high_scores(&self) -> &HighScoreTable {
+        &self.high_scores
+    }
+    pub fn mixer_mut(&mut self) -> &mut Mixer {
+        &mut self.mixer
+    }
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
+    }
+    pub fn is_debug_overlay_on(&self) -> bool {
+        self.is_debug_overlay_on
+    }
+    pub fn set_debug_overlay(&mut self, is_on: bool) {
+        self.is_debug_overlay_on = is_on;
+    }
+    pub fn time(&self) -> &TimeControl {
+        &self.time
+    }
+    pub fn time_mut(&mut self) -> &mut TimeControl {
+        &mut self.time
+    }
+    pub fn demo(&self) -> &World {
+        &self.demo
+    }
+    pub fn is_autopilot_on(&self) -> bool {
+        self.autopilot.is_some()
+    }
+    pub fn set_autopilot(&mut self, is_on: bool) {
+        self.autopilot = is_on.then(Autopilot::default);
+    }
+}
+impl Game {
+    /// Handle input and step the world according to the current [GameState]
+    pub fn update(&mut self) {
+        if is_key_pressed(Console::TOGGLE_KEY) {
+            self.console.toggle();
+        }
+        if is_key_pressed(Self::DEBUG_OVERLAY_KEY) {
+            self.is_debug_overlay_on = !self.is_debug_overlay_on;
+        }
+        if self.console.is_open() {
+            if let Some(line) = self.console.handle_input() {
+                self.run_console_line(&line);
+            }
+            self.mixer.set_thrust(false);
+            return;
+        }
+
+        let is_pause_pressed = Self::PAUSE_KEYS.into_iter().any(is_key_pressed);
+
+        if is_key_pressed(Self::RENDER_MODE_KEY) && self.state != GameState::EnterInitials {
+            self.render_mode = self.render_mode.toggled();
+        }
+
+        self.state = match self.state {
+            GameState::Title if is_key_pressed(Self::START_KEY) => {
+                self.set_world(World::new(self.arena));
+                self.new_high_score_rank = None;
+                GameState::Playing
+            }
+            GameState::Title if is_key_pressed(Self::LARGE_WORLD_KEY) => {
+                self.set_world(World::new_large(self.arena.dimensions()));
+                self.new_high_score_rank = None;
+                GameState::Playing
+            }
+            GameState::Title if is_key_pressed(Self::CONTINUE_KEY) => match World::load() {
+                // start paused so the player has a moment to get ready
+                Some(world) => {
+                    self.set_world(world);
+                    self.new_high_score_rank = None;
+                    self.save_message = None;
+                    GameState::Paused
+                }
+                None => {
+                    self.has_saved_game = false;
+                    GameState::Title
+                }
+            },
+            GameState::Playing if is_pause_pressed => {
+                self.save_message = None;
+                GameState::Paused
+            }
+            GameState::Playing => {
+                self.handle_time_input();
+                // the keyboard is read once per frame, but held keys count for every step so turning takes as long
+                // in slow motion as anything else. A press only fires once, on the next step there is
+                let keys = Keyboard.actions(&self.world);
+                self.is_fire_latched |= keys.fire && self.autopilot.is_none();
+                for _ in 0..self.time.steps_this_frame() {
+                    let actions = match &mut self.autopilot {
+                        Some(autopilot) => autopilot.actions(&self.world),
+                        None => Actions {
+                            fire: std::mem::take(&mut self.is_fire_latched),
+                            ..keys
+                        },
+                    };
+                    self.world.apply_actions(actions);
+                    self.step_world();
+                }
+                let is_thrusting = self.world.player().is_thrusting() && !self.time.is_frozen();
+                self.mixer.set_thrust(is_thrusting);
+                if !self.world.is_over() {
+                    GameState::Playing
+                } else if self.high_scores.qualifies(self.world.score()) {
+                    // don't count anything typed while playing as initials
+                    clear_input_queue();
+                    self.initials.clear();
+                    GameState::EnterInitials
+                } else {
+                    GameState::GameOver
+                }
+            }
+            GameState::Paused if is_pause_pressed => GameState::Playing,
+            GameState::Paused if is_key_pressed(Self::SAVE_KEY) => {
+                self.save_message = Some(match self.world.save() {
+                    Ok(()) => String::from("game saved"),
+                    Err(error) => format!("failed to save: {}", error),
+                });
+                self.has_saved_game = World::has_save();
+                GameState::Paused
+            }
+            GameState::Paused => {
+                self.handle_volume_input();
+                GameState::Paused
+            }
+            GameState::EnterInitials => self.handle_initials_input(),
+            GameState::GameOver if is_key_pressed(Self::START_KEY) => GameState::Title,
+            GameState::Title => {
+                self.step_demo();
+                GameState::Title
+            }
+            state => state,
+        };
+
+        // nothing should keep thrusting once the game stops
+        if self.state != GameState::Playing {
+            self.mixer.set_thrust(false);
+        }
+    }
+    /// Replaces the world, with the heartbeat starting over from its asteroids
+    fn set_world(&mut self, mut world: World) {
+        world.set_config(self.config.clone());
+        self.world = world;
+        self.is_fire_latched = false;
+        self.heartbeat.start_wave(self.world.asteroids());
+    }
+    /// One step of the title screen demo, starting a new one once the autopilot runs out of lives
+    fn step_demo(&mut self) {
+        let actions = self.demo_pilot.actions(&self.demo);
+        self.demo.apply_actions(actions);
+        self.demo.step();
+        // the demo is silent
+        self.demo.take_events();
+        if self.demo.is_over() {
+            self.demo = World::new(self.arena);
+            self.demo.set_config(self.config.clone());
+            self.demo_pilot = Autopilot::default();
+        }
+    }
+    /// Steps the world, then plays the sounds for what happened in it and keeps the heartbeat going
+    fn step_world(&mut self) {
+        self.world.step();
+        for event in self.world.take_events() {
+            if let Some(effect) = SoundEffect::for_event(&event) {
+                self.mixer.play(effect);
+            }
+            if let GameEvent::WaveCleared { .. } = event {
+                self.heartbeat.start_wave(self.world.asteroids());
+            }
+        }
+        self.heartbeat.step(self.world.asteroids(), &mut self.mixer);
+    }
+    /// MINUS and EQUALS change the time scale, F5 freezes and F6 steps a single frame while frozen
+    fn handle_time_input(&mut self) {
+        if is_key_pressed(Self::SLOWER_KEY) {
+            self.time.slower();
+        }
+        if is_key_pressed(Self::FASTER_KEY) {
+            self.time.faster();
+        }
+        if is_key_pressed(Self::FREEZE_KEY) {
+            let is_frozen = !self.time.is_frozen();
+            self.time.set_frozen(is_frozen);
+        }
+        if is_key_pressed(Self::ADVANCE_KEY) && self.time.is_frozen() {
+            self.time.advance(1);
+        }
+    }
+    /// Runs `line` as a [Command] and logs what happened
+    fn run_console_line(&mut self, line: &str) {
+        let result = Command::parse(line).and_then(|command| self.run_command(command));
+        match result {
+            Ok(output) => output.lines().for_each(|line| self.console.log(line)),
+            Err(error) => self.console.log(format!("error: {error}")),
+        }
+    }
+    fn run_command(&mut self, command: Command) -> Result<String, String> {
+        let output = match command {
+            Command::Spawn { count, position } => {
+                self.world.spawn_asteroids(count, position);
+                self.heartbeat.start_wave(self.world.asteroids());
+                format!("spawned {count} asteroids")
+            }
+            Command::Lives(lives) => {
+                self.world.set_lives(lives);
+                format!("lives set to {lives}")
+            }
+            Command::GodMode => {
+                let is_god_mode = !self.world.is_god_mode();
+                self.world.set_god_mode(is_god_mode);
+                format!("god mode {}", if is_god_mode { "on" } else { "off" })
+            }
+            Command::Config { key: None, .. } => self
+                .config
+                .entries()
+                .into_iter()
+                .map(|(key, value)| format!("{key} = {value}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            Command::Config {
+                key: Some(key),
+                value: None,
+            } => format!("{key} = {}", self.config.get(&key)?),
+            Command::Config {
+                key: Some(key),
+                value: Some(value),
+            } => {
+                let mut config = self.config.clone();
+                config.set(&key, &value)?;
+                self.set_config(config);
+                format!("{key} = {}", self.config.get(&key)?)
+            }
+            Command::Seed(seed) => {
+                rand::srand(seed);
+                format!("random seed set to {seed}")
+            }
+            Command::Step(frames) => {
+                if !matches!(self.state, GameState::Playing | GameState::Paused) {
+                    return Err(String::from("there is no game being played"));
+                }
+                for _ in 0..frames {
+                    self.step_world();
+                }
+                format!("stepped {frames} frames")
+            }
+            Command::TimeScale(scale) => {
+                self.time.set_scale(scale);
+                format!("time scale set to {}", self.time.scale())
+            }
+            Command::Autopilot => {
+                let is_on = !self.is_autopilot_on();
+                self.set_autopilot(is_on);
+                format!("autopilot {}", if is_on { "on" } else { "off" })
+            }
+            Command::Help => Command::HELP.join("\n"),
+        };
+        Ok(output)
+    }
+    /// UP and DOWN pick a volume, LEFT and RIGHT change it
+    fn handle_volume_input(&mut self) {
+        let count = VolumeSetting::ALL.len();
+        let index = VolumeSetting::ALL
+            .iter()
+            .position(|&setting| setting == self.selected_volume)
+            .unwrap_or(0);
+        if is_key_pressed(KeyCode::Down) {
+            self.selected_volume = VolumeSetting::ALL[(index + 1) % count];
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected_volume = VolumeSetting::ALL[(index + count - 1) % count];
+        }
+
+        let mut delta = 0.0;
+        if is_key_pressed(KeyCode::Right) {
+            delta += Mixer::VOLUME_STEP;
+        }
+        if is_key_pressed(KeyCode::Left) {
+            delta -= Mixer::VOLUME_STEP;
+        }
+        if delta == 0.0 {
+            return;
+        }
+        match self.selected_volume {
+            VolumeSetting::Master => {
+                let volume = self.mixer.master_volume() + delta;
+                self.mixer.set_master_volume(volume);
+            }
+            VolumeSetting::Music => {
+                let volume = self.mixer.music_volume() + delta;
+                self.mixer.set_music_volume(volume);
+            }
+            VolumeSetting::Sfx => {
+                let volume = self.mixer.sfx_volume() + delta;
+                self.mixer.set_sfx_volume(volume);
+            }
+        }
+    }
+    /// Type letters, BACKSPACE to erase and ENTER to submit once all initials are in
+    fn handle_initials_input(&mut self) -> GameState {
+        while let Some(character) = get_char_pressed() {
+            if character.is_ascii_alphabetic()
+                && self.initials.len() < HighScoreTable::INITIALS_LENGTH
+            {
+                self.initials.push(character.to_ascii_uppercase());
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.initials.pop();
+        }
+
+        let is_done = self.initials.len() == HighScoreTable::INITIALS_LENGTH;
+        if is_done && is_key_pressed(Self::START_KEY) {
+            self.new_high_score_rank = self.high_scores.insert(&self.initials, self.world.score());
+            self.high_scores.save();
+            GameState::GameOver
+        } else {
+            GameState::EnterInitials
+        }
+    }
+}
+impl Game {
+    /// Lists the volumes starting `y_offset` below the middle of the view, highlighting the selected one
+    fn draw_volumes(&self, renderer: &mut dyn Renderer, y_offset: f32) {
+        const FONT_SIZE: f32 = 24.0;
+        for (index, setting) in VolumeSetting::ALL.into_iter().enumerate() {
+            let (name, volume) = match setting {
+                VolumeSetting::Master => ("master", self.mixer.master_volume()),
+                VolumeSetting::Music => ("music", self.mixer.music_volume()),
+                VolumeSetting::Sfx => ("sfx", self.mixer.sfx_volume()),
+            };
+            let line = format!("{:<6} {:>3}%", name, (volume * 100.0).round());
+            let color = if setting == self.selected_volume {
+                YELLOW
+            } else {
+                WHITE
+            };
+            let y = y_offset + index as f32 * FONT_SIZE;
+            self.draw_centered_text(renderer, &line, y, FONT_SIZE, color);
+        }
+    }
+    /// The time scale at the top of the view, unless the game is running normally
+    fn draw_time(&self, renderer: &mut dyn Renderer) {
+        const FONT_SIZE: f32 = 24.0;
+        let text = if self.time.is_frozen() {
+            String::from("FROZEN  F6 to step")
+        } else if self.time.scale() != 1.0 {
+            format!("{}x", self.time.scale())
+        } else {
+            return;
+        };
+        let y_offset = FONT_SIZE - self.world.camera().view().y / 2.0;
+        self.draw_centered_text(renderer, &text, y_offset, FONT_SIZE, YELLOW);
+    }
+    /// Lists the high scores starting `y_offset` below the middle of the view
+    fn draw_high_scores(&self, renderer: &mut dyn Renderer, y_offset: f32) {
+        const FONT_SIZE: f32 = 24.0;
+        for (rank, entry) in self.high_scores.scores().iter().enumerate() {
+            let line = format!("{:>2}. {} {:>8}", rank + 1, entry.initials, entry.score);
+            let color = if self.new_high_score_rank == Some(rank) {
+                YELLOW
+            } else {
+                WHITE
+            };
+            let y = y_offset + rank as f32 * FONT_SIZE;
+            self.draw_centered_text(renderer, &line, y, FONT_SIZE, color);
+        }
+    }
+}
+impl Game {
+    /// Draws `text` horizontally centered, `y_offset` below the middle of the view
+    fn draw_centered_text(
+        &self,
+        renderer: &mut dyn Renderer,
+        text: &str,
+        y_offset: f32,
+        font_size: f32,
+        color: Color,
+    ) {
+        let dimensions = renderer.measure_text(text, font_size);
+        let origin = self.world.camera().view() / 2.0;
+        let position = vec2(origin.x - dimensions.x / 2.0, origin.y + y_offset);
+        renderer.draw_text(text, position, font_size, color);
+    }
+    /// Draws the current [GameState], using `draw_world` for the world itself
+    fn draw_state(&self, renderer: &mut dyn Renderer, draw_world: fn(&World, &mut dyn Renderer)) {
+        match self.state {
+            GameState::Title => {
+                draw_world(&self.demo, renderer);
+                self.draw_centered_text(renderer, "ASTEROIDS", -160.0, 96.0, WHITE);
+                self.draw_centered_text(renderer, "press ENTER to start", -104.0, 32.0, WHITE);
+                let large = "press L for a large world";
+                self.draw_centered_text(renderer, large, -72.0, 32.0, WHITE);
+                if self.has_saved_game {
+                    self.draw_centered_text(renderer, "press C to continue", -40.0, 32.0, WHITE);
+                }
+                self.draw_high_scores(renderer, 8.0);
+            }
+            GameState::Playing => draw_world(&self.world, renderer),
+            GameState::Paused => {
+                draw_world(&self.world, renderer);
+                self.draw_centered_text(renderer, "PAUSED", 0.0, 64.0, WHITE);
+                self.draw_centered_text(renderer, "press P to resume", 48.0, 32.0, WHITE);
+                self.draw_centered_text(renderer, "press S to save", 80.0, 32.0, WHITE);
+                if let Some(message) = &self.save_message {
+                    self.draw_centered_text(renderer, message, 128.0, 32.0, YELLOW);
+                }
+                self.draw_volumes(renderer, 192.0);
+            }
+            GameState::EnterInitials => {
+                let score = format!("new high score {}", self.world.score());
+                let initials = format!("{:_<1$}", self.initials, HighScoreTable::INITIALS_LENGTH);
+                self.draw_centered_text(renderer, &score, 0.0, 48.0, WHITE);
+                self.draw_centered_text(renderer, "type your initials", 64.0, 32.0, WHITE);
+                self.draw_centered_text(renderer, &initials, 128.0, 64.0, YELLOW);
+            }
+            GameState::GameOver => {
+                let score = format!("final score {}", self.world.score());
+                self.draw_centered_text(renderer, "GAME OVER", -160.0, 96.0, WHITE);
+                self.draw_centered_text(renderer, &score, -96.0, 32.0, WHITE);
+                self.draw_high_scores(renderer, -32.0);
+                self.draw_centered_text(renderer, "press ENTER to continue", 272.0, 32.0, WHITE);
+            }
+        }
+    }
+}
+impl Draw for Game {
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        match self.render_mode {
+            RenderMode::Sprite => self.draw_state(renderer, World::draw),
+            RenderMode::Vector => self
+                .vector_display
+                .get_or_init(|| VectorDisplay::new(self.world.camera()))
+                .draw(self, self.world.camera(), renderer),
+        }
+        // on top of the vector display's glow
+        let is_world_shown = matches!(self.state, GameState::Playing | GameState::Paused);
+        if self.is_debug_overlay_on && is_world_shown {
+            self.world.draw_debug(renderer);
+        }
+        if self.state == GameState::Playing {
+            self.draw_time(renderer);
+        }
+        if self.console.is_open() {
+            self.console.draw(renderer, self.world.camera().view().x);
+        }
+    }
+    fn draw_outline(&self, renderer: &mut dyn Renderer) {
+        self.draw_state(renderer, World::draw_outline);
+    }
+}
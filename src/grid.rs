@@ -0,0 +1,98 @@
+//! A uniform grid broad-phase, so collisions don't have to test every pair of things
+//! - the arena is split into equally sized cells
+//! - everything is inserted into each cell its bounding box touches. Boxes crossing an edge wrap around into the cells on the other side
+//! - a query returns everything sharing a cell with the queried box. These are only candidates, the exact test still has to be done
+
+use crate::Arena;
+use macroquad::prelude::*;
+
+pub struct SpatialGrid {
+    columns: usize,
+    rows: usize,
+    /// The arena divided evenly into `columns` by `rows`, so close to but not exactly the requested size
+    cell_size: Vec2,
+    /// Indices of what was inserted, row by row
+    cells: Vec<Vec<usize>>,
+}
+impl SpatialGrid {
+    /// An empty grid with cells about `cell_size` wide and tall
+    pub fn new(arena: &Arena, cell_size: f32) -> Self {
+        let arena = arena.dimensions();
+        let columns = (arena.x / cell_size).ceil().max(1.0) as usize;
+        let rows = (arena.y / cell_size).ceil().max(1.0) as usize;
+        Self {
+            columns,
+            rows,
+            cell_size: arena / vec2(columns as f32, rows as f32),
+            cells: vec![Vec::new(); columns * rows],
+        }
+    }
+    pub fn cell_size(&self) -> Vec2 {
+        self.cell_size
+    }
+    /// The top left corner of every cell with something in it, and how many things are in it
+    pub fn occupied_cells(&self) -> impl Iterator<Item = (Vec2, usize)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| !cell.is_empty())
+            .map(|(index, cell)| {
+                let column = (index % self.columns) as f32;
+                let row = (index / self.columns) as f32;
+                (vec2(column, row) * self.cell_size, cell.len())
+            })
+    }
+    pub fn clear(&mut self) {
+        self.cells.iter_mut().for_each(Vec::clear);
+    }
+    /// Adds `index` to every cell touched by the box around the circle at `center`
+    pub fn insert(&mut self, index: usize, center: Vec2, radius: f32) {
+        let cells: Vec<usize> = self.cells_around(center, radius).collect();
+        for cell in cells {
+            self.cells[cell].push(index);
+        }
+    }
+    /// Every index inserted into a cell touched by the box around the circle at `center`, in increasing order
+    pub fn candidates(&self, center: Vec2, radius: f32) -> Vec<usize> {
+        let mut candidates: Vec<usize> = self
+            .cells_around(center, radius)
+            .flat_map(|cell| self.cells[cell].iter().copied())
+            .collect();
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+    /// `(inserted, other)` for every inserted index that might touch one of the `others` circles, given as `(center, radius)`.
+    /// Sorted by the inserted index, then by the index into `others`
+    pub fn candidate_pairs(&self, others: &[(Vec2, f32)]) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = others
+            .iter()
+            .enumerate()
+            .flat_map(|(other, &(center, radius))| {
+                self.candidates(center, radius)
+                    .into_iter()
+                    .map(move |inserted| (inserted, other))
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs
+    }
+}
+impl SpatialGrid {
+    /// The index of every cell the box around the circle touches, each only once even if the box is bigger than the arena
+    fn cells_around(&self, center: Vec2, radius: f32) -> impl Iterator<Item = usize> + '_ {
+        let first = ((center - radius) / self.cell_size).floor();
+        let last = ((center + radius) / self.cell_size).floor();
+        let column_count = ((last.x - first.x) as usize + 1).min(self.columns);
+        let row_count = ((last.y - first.y) as usize + 1).min(self.rows);
+
+        (0..row_count).flat_map(move |row_step| {
+            let row = (first.y as i64 + row_step as i64).rem_euclid(self.rows as i64) as usize;
+            (0..column_count).map(move |column_step| {
+                let column =
+                    (first.x as i64 + column_step as i64).rem_euclid(self.columns as i64) as usize;
+                row * self.columns + column
+            })
+        })
+    }
+}
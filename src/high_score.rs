@@ -0,0 +1,161 @@
+//! Keep the best scores between runs of the game
+//! - only the top [HighScoreTable::CAPACITY] scores are kept
+//! - every score is labeled with the player's initials
+//! - the table is saved with a version number so the format can change later
+//! - a missing, broken or outdated save just starts an empty table
+
+use crate::storage;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: usize,
+}
+
+/// What actually gets written to storage
+#[derive(Serialize, Deserialize)]
+struct HighScoreFile {
+    version: u32,
+    scores: Vec<HighScore>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighScoreTable {
+    /// Sorted from highest to lowest score
+    scores: Vec<HighScore>,
+}
+impl HighScoreTable {
+    pub const CAPACITY: usize = 10;
+    pub const INITIALS_LENGTH: usize = 3;
+    pub const VERSION: u32 = 1;
+    const STORAGE_KEY: &'static str = "high_scores.json";
+}
+impl HighScoreTable {
+    /// Loads the saved table, or an empty one if it is missing or can't be read
+    pub fn load() -> Self {
+        let Some(json) = storage::load(Self::STORAGE_KEY) else {
+            return Self::default();
+        };
+        Self::from_json(&json).unwrap_or_else(|| {
+            eprintln!("high score table is corrupted or outdated; starting a new one");
+            Self::default()
+        })
+    }
+    pub fn save(&self) {
+        if let Err(error) = storage::save(Self::STORAGE_KEY, &self.to_json()) {
+            eprintln!("failed to save high score table: {}", error);
+        }
+    }
+    /// Returns [None] if `json` isn't a table with the current [Self::VERSION]
+    pub fn from_json(json: &str) -> Option<Self> {
+        let file = serde_json::from_str::<HighScoreFile>(json).ok()?;
+        if file.version != Self::VERSION {
+            return None;
+        }
+
+        // don't trust the file to be sorted or the right size
+        let mut table = Self::default();
+        for HighScore { initials, score } in file.scores {
+            table.insert(&initials, score);
+        }
+        Some(table)
+    }
+    pub fn to_json(&self) -> String {
+        let file = HighScoreFile {
+            version: Self::VERSION,
+            scores: self.scores.clone(),
+        };
+        serde_json::to_string_pretty(&file).expect("high scores are always serializable")
+    }
+}
+impl HighScoreTable {
+    pub fn scores(&self) -> &[HighScore] {
+        &self.scores
+    }
+    /// Would `score` make it onto the table?
+    pub fn qualifies(&self, score: usize) -> bool {
+        score > 0
+            && (self.scores.len() < Self::CAPACITY
+                || self
+                    .scores
+                    .last()
+                    .is_some_and(|lowest| score > lowest.score))
+    }
+    /// Adds a score to the table and returns its rank (starting at 0), or [None] if it didn't make the cut.
+    /// `initials` are uppercased and cut to [Self::INITIALS_LENGTH]
+    pub fn insert(&mut self, initials: &str, score: usize) -> Option<usize> {
+        if !self.qualifies(score) {
+            return None;
+        }
+        let initials = initials
+            .chars()
+            .take(Self::INITIALS_LENGTH)
+            .collect::<String>()
+            .to_uppercase();
+
+        // ties go below the existing score
+        let rank = self.scores.partition_point(|entry| entry.score >= score);
+        self.scores.insert(rank, HighScore { initials, score });
+        self.scores.truncate(Self::CAPACITY);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let mut table = HighScoreTable::default();
+        table.insert("abc", 300);
+        table.insert("xyz", 500);
+        assert_eq!(HighScoreTable::from_json(&table.to_json()), Some(table));
+    }
+
+    #[test]
+    fn corrupt_file_is_rejected() {
+        assert_eq!(HighScoreTable::from_json("not json"), None);
+        assert_eq!(HighScoreTable::from_json(r#"{"version": 1}"#), None);
+    }
+
+    #[test]
+    fn other_version_is_rejected() {
+        let json = r#"{"version": 2, "scores": [{"initials": "ABC", "score": 100}]}"#;
+        assert_eq!(HighScoreTable::from_json(json), None);
+    }
+
+    #[test]
+    fn loaded_scores_are_cleaned_up() {
+        let json = r#"{"version": 1, "scores": [
+            {"initials": "low", "score": 100},
+            {"initials": "toolong", "score": 900},
+            {"initials": "ZER", "score": 0}
+        ]}"#;
+        let table = HighScoreTable::from_json(json).unwrap();
+        let scores: Vec<(&str, usize)> = table
+            .scores()
+            .iter()
+            .map(|entry| (entry.initials.as_str(), entry.score))
+            .collect();
+        // sorted, initials cut and uppercased, and the zero score dropped
+        assert_eq!(scores, [("TOO", 900), ("LOW", 100)]);
+    }
+
+    #[test]
+    fn only_the_best_scores_are_kept() {
+        let scores = (1..=HighScoreTable::CAPACITY + 5).map(|score| HighScore {
+            initials: String::from("AAA"),
+            score,
+        });
+        let file = HighScoreFile {
+            version: HighScoreTable::VERSION,
+            scores: scores.collect(),
+        };
+        let table = HighScoreTable::from_json(&serde_json::to_string(&file).unwrap()).unwrap();
+        assert_eq!(table.scores().len(), HighScoreTable::CAPACITY);
+        assert_eq!(table.scores()[0].score, HighScoreTable::CAPACITY + 5);
+        assert!(!table.qualifies(5));
+    }
+}
@@ -0,0 +1,129 @@
+//! Picks up changes to files while the game is running, native only
+//! - [GameConfig::PATH] is read on the first update and again whenever it changes, and applied to the [Game] straight away
+//! - a png replaced in [HotReload::ASSETS_DIRECTORY] replaces the texture of its [Sprite]. Sounds still need a restart
+//! - files are checked by their modified time every [HotReload::CHECK_INTERVAL_FRAMES], so there is nothing to set up
+//! - a file that fails to load leaves everything as it was, and the error is drawn on screen until the file is fixed
+
+use crate::{replace_texture, Draw, Game, GameConfig, Renderer, Sprite, World};
+use macroquad::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+pub struct HotReload {
+    /// When each watched file was last modified, as of the last check
+    modified: HashMap<PathBuf, SystemTime>,
+    frames_until_check: usize,
+    /// Why loading each broken file failed
+    errors: BTreeMap<PathBuf, String>,
+}
+impl HotReload {
+    pub const ASSETS_DIRECTORY: &'static str = "assets";
+    pub const CHECK_INTERVAL_FRAMES: usize = 30;
+}
+impl Default for HotReload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl HotReload {
+    /// The assets as they are now are already in the game, but the config still has to be read
+    pub fn new() -> Self {
+        let modified = Self::asset_files()
+            .into_iter()
+            .filter_map(|path| Some((path.clone(), Self::modified_time(&path)?)))
+            .collect();
+        Self {
+            modified,
+            frames_until_check: 0,
+            errors: BTreeMap::new(),
+        }
+    }
+    pub fn errors(&self) -> impl Iterator<Item = &str> {
+        self.errors.values().map(String::as_str)
+    }
+    /// Call once per frame. Reloads whatever changed since the last check
+    pub fn update(&mut self, game: &mut Game) {
+        if self.frames_until_check > 0 {
+            self.frames_until_check -= 1;
+            return;
+        }
+        self.frames_until_check = Self::CHECK_INTERVAL_FRAMES;
+
+        for path in self.changed_files() {
+            match Self::reload(&path, game) {
+                Ok(()) => self.errors.remove(&path),
+                Err(error) => self
+                    .errors
+                    .insert(path.clone(), format!("{}: {error}", path.display())),
+            };
+        }
+    }
+}
+impl HotReload {
+    fn asset_files() -> Vec<PathBuf> {
+        fs::read_dir(Self::ASSETS_DIRECTORY)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .collect()
+    }
+    fn modified_time(path: &Path) -> Option<SystemTime> {
+        fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+    /// Every watched file that was modified, created or deleted since the last check
+    fn changed_files(&mut self) -> Vec<PathBuf> {
+        let mut watched = Self::asset_files();
+        watched.push(PathBuf::from(GameConfig::PATH));
+
+        let mut changed = Vec::new();
+        for path in watched {
+            let modified = Self::modified_time(&path);
+            if modified != self.modified.get(&path).copied() {
+                changed.push(path.clone());
+            }
+            match modified {
+                Some(modified) => self.modified.insert(path, modified),
+                None => self.modified.remove(&path),
+            };
+        }
+        changed
+    }
+    fn reload(path: &Path, game: &mut Game) -> Result<(), String> {
+        if path == Path::new(GameConfig::PATH) {
+            let config = GameConfig::load().map_err(|error| error.to_string())?;
+            game.set_config(config);
+            return Ok(());
+        }
+        let sprite = match path.file_name().and_then(|name| name.to_str()) {
+            Some("duck.png") => Sprite::Duck,
+            Some("rock.png") => Sprite::Rock,
+            _ => return Ok(()),
+        };
+        // a deleted png keeps the texture it had, it might be in the middle of being replaced
+        let Ok(png) = fs::read(path) else {
+            return Ok(());
+        };
+        replace_texture(sprite, &png).map_err(|error| error.to_string())
+    }
+}
+impl Draw for HotReload {
+    /// The errors in the top left corner under the HUD, on top of everything else
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        const FONT_SIZE: f32 = 20.0;
+        const MARGIN: f32 = 8.0;
+        let mut position = vec2(MARGIN, World::HUD_HEIGHT + MARGIN);
+        for error in self.errors() {
+            for line in error.lines() {
+                position.y += FONT_SIZE;
+                renderer.draw_text(line, position, FONT_SIZE, RED);
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use crate::Arena;
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// This trait provides default implementations of [Kinematic]'s getters if `Self` can provide a reference to a [Kinematic]
 pub trait KinematicGetters {
@@ -30,27 +32,31 @@ pub trait KinematicMutators {
     fn cap_speed(&mut self, max_speed: f32) {
         self.kinematic_mut().cap_speed(max_speed);
     }
-    fn keep_on_screen(&mut self) {
-        self.kinematic_mut().keep_on_screen();
+    fn keep_in_arena(&mut self, arena: &Arena) {
+        self.kinematic_mut().keep_in_arena(arena);
     }
     fn step_motion(&mut self) {
         self.kinematic_mut().step_motion();
     }
-    fn step_friction(&mut self) {
-        self.kinematic_mut().step_friction();
+    fn step_friction(&mut self, friction: f32) {
+        self.kinematic_mut().step_friction(friction);
     }
     fn apply_acceleration(&mut self, acceleration: Vec2) {
         self.kinematic_mut().apply_acceleration(acceleration);
     }
 }
 
-/// This struct is responsible for movement in [crate::Bullet] and [crate::Asteroid]
+/// This struct is responsible for movement in every [crate::Entity]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Kinematic {
     position: Vec2,
     velocity: Vec2,
     acceleration: Vec2,
 }
 impl Kinematic {
+    /// The default fraction of velocity and acceleration lost every [Self::step_friction]
+    pub const FRICTION: f32 = 0.02;
+
     pub const fn new(position: Vec2, velocity: Vec2, acceleration: Vec2) -> Self {
         Self {
             position,
@@ -90,22 +96,23 @@ impl Kinematic {
         }
     }
 
-    pub fn keep_on_screen(&mut self) {
+    pub fn keep_in_arena(&mut self, arena: &Arena) {
         // take a peek forward in time!
         let next_position = self.position + self.velocity;
+        let dimensions = arena.dimensions();
 
         // next frame player will travel off the left side
         if next_position.x < 0.0 {
             // so lets teleport them to the right side
-            self.position.x = screen_width();
+            self.position.x = dimensions.x;
         }
-        if next_position.x > screen_width() {
+        if next_position.x > dimensions.x {
             self.position.x = 0.0;
         }
         if next_position.y < 0.0 {
-            self.position.y = screen_height();
+            self.position.y = dimensions.y;
         }
-        if next_position.y > screen_height() {
+        if next_position.y > dimensions.y {
             self.position.y = 0.0;
         }
     }
@@ -119,9 +126,9 @@ impl Kinematic {
         self.velocity = next_velocity;
     }
 
-    pub fn step_friction(&mut self) {
+    pub fn step_friction(&mut self, friction: f32) {
         // apply friction (using linear interpolation with <0, 0> aka lerp)
-        self.acceleration += self.acceleration * -0.02;
-        self.velocity += self.velocity * -0.02;
+        self.acceleration += self.acceleration * -friction;
+        self.velocity += self.velocity * -friction;
     }
 }
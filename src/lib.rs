@@ -1,10 +1,40 @@
+pub mod arena;
 pub mod assets;
 pub mod asteroid;
+pub mod audio;
 pub mod bullet;
+pub mod camera;
+pub mod config;
+pub mod console;
+pub mod controller;
+pub mod debug;
+pub mod entity;
+pub mod environment;
+pub mod event;
+pub mod game;
+pub mod grid;
+pub mod high_score;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod hot_reload;
 pub mod kinematic;
+pub mod music;
 pub mod player;
-
-pub use crate::{assets::*, asteroid::*, bullet::*, kinematic::*, player::*};
+pub mod renderer;
+pub mod software;
+pub mod storage;
+pub mod time;
+pub mod vector;
+pub mod world;
+pub mod wrap;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::hot_reload::*;
+pub use crate::{
+    arena::*, assets::*, asteroid::*, audio::*, bullet::*, camera::*, config::*, console::*,
+    controller::*, entity::*, environment::*, event::*, game::*, grid::*, high_score::*,
+    kinematic::*, music::*, player::*, renderer::*, software::*, time::*, vector::*, world::*,
+    wrap::*,
+};
 use macroquad::prelude::*;
 
 pub const FRAC_SQRT3_2: f32 = 0.86602540378443864676372317075294;
@@ -43,11 +73,77 @@ pub fn is_point_in_polygon(point: Vec2, polygon_vertices: &[Vec2]) -> bool {
     is_inside_polygon
 }
 
+/// Do the segments from `a_start` to `a_end` and from `b_start` to `b_end` cross or touch?
+/// Parallel segments never count, even if they overlap
+pub fn do_segments_intersect(a_start: Vec2, a_end: Vec2, b_start: Vec2, b_end: Vec2) -> bool {
+    let a = a_end - a_start;
+    let b = b_end - b_start;
+    let denominator = a.perp_dot(b);
+    if denominator == 0.0 {
+        return false;
+    }
+    // how far along each segment the lines cross, from 0 at the start to 1 at the end
+    let offset = b_start - a_start;
+    let along_a = offset.perp_dot(b) / denominator;
+    let along_b = offset.perp_dot(a) / denominator;
+    (0.0..=1.0).contains(&along_a) && (0.0..=1.0).contains(&along_b)
+}
+
+/// Is any part of the segment from `start` to `end` inside the polygon?
+pub fn is_segment_in_polygon(start: Vec2, end: Vec2, polygon_vertices: &[Vec2]) -> bool {
+    if is_point_in_polygon(end, polygon_vertices) || is_point_in_polygon(start, polygon_vertices) {
+        return true;
+    }
+    // both ends are outside, so the segment has to cross an edge to get inside
+    let vertex_count = polygon_vertices.len();
+    (0..vertex_count).any(|i| {
+        let edge_start = polygon_vertices[i];
+        let edge_end = polygon_vertices[(i + 1) % vertex_count];
+        do_segments_intersect(start, end, edge_start, edge_end)
+    })
+}
+
+/// Do the two polygons overlap at all? True if either is completely inside the other
+pub fn do_polygons_overlap(a_vertices: &[Vec2], b_vertices: &[Vec2]) -> bool {
+    let vertex_count = a_vertices.len();
+    let is_edge_in_b = (0..vertex_count).any(|i| {
+        let edge_start = a_vertices[i];
+        let edge_end = a_vertices[(i + 1) % vertex_count];
+        is_segment_in_polygon(edge_start, edge_end, b_vertices)
+    });
+    // no edge of `a` touches `b`, so `b` can only be entirely inside `a`
+    is_edge_in_b
+        || b_vertices
+            .first()
+            .is_some_and(|&vertex| is_point_in_polygon(vertex, a_vertices))
+}
+
+/// The smallest and largest corners of the box around `points`, or [None] if there are no points
+pub fn bounding_box(points: &[Vec2]) -> Option<(Vec2, Vec2)> {
+    let first = *points.first()?;
+    Some(points.iter().fold((first, first), |(min, max), &point| {
+        (min.min(point), max.max(point))
+    }))
+}
+
 /// returns a rectangular Vec2 with the given `norm` and `angle`. `angle` is relative to the positive x axis
 pub fn polar_vec2(norm: f32, angle: f32) -> Vec2 {
     vec2(angle.cos(), angle.sin()) * norm
 }
 
+/// The shortest Vec2 from `from` to `to` in an `arena` that wraps around at its edges.
+/// Each component is never more than half the arena's size
+pub fn toroidal_delta(from: Vec2, to: Vec2, arena: Vec2) -> Vec2 {
+    let delta = to - from;
+    // going the other way around is shorter once it's more than half way across
+    delta - arena * (delta / arena).round()
+}
+
+/// The length of [toroidal_delta]
+pub fn toroidal_distance(from: Vec2, to: Vec2, arena: Vec2) -> f32 {
+    toroidal_delta(from, to, arena).length()
+}
+
 pub trait RotationMatrix {
     fn rotation_matrix(&self) -> Mat2;
 }
@@ -63,7 +159,11 @@ impl RotationMatrix for f32 {
 }
 
 pub trait Draw {
-    fn draw(&self);
+    fn draw(&self, renderer: &mut dyn Renderer);
+    /// Draw only outlines, for [RenderMode::Vector]. Defaults to [Self::draw]
+    fn draw_outline(&self, renderer: &mut dyn Renderer) {
+        self.draw(renderer);
+    }
 }
 
 pub fn initialize_rng() {
@@ -71,10 +171,40 @@ pub fn initialize_rng() {
     rand::srand(current_time);
 }
 
-pub fn screen_dimensions() -> Vec2 {
-    vec2(screen_width(), screen_height())
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARENA: Vec2 = vec2(800.0, 600.0);
+
+    #[test]
+    fn toroidal_delta_goes_across_the_seam() {
+        let delta = toroidal_delta(vec2(795.0, 300.0), vec2(5.0, 300.0), ARENA);
+        assert_eq!(delta, vec2(10.0, 0.0));
+        assert_eq!(
+            toroidal_distance(vec2(795.0, 300.0), vec2(5.0, 300.0), ARENA),
+            10.0
+        );
+        // and back the other way
+        assert_eq!(
+            toroidal_delta(vec2(5.0, 300.0), vec2(795.0, 300.0), ARENA),
+            vec2(-10.0, 0.0)
+        );
+    }
 
-pub fn screen_origin() -> Vec2 {
-    screen_dimensions() / 2.0
+    #[test]
+    fn toroidal_delta_goes_across_the_seam_vertically() {
+        let delta = toroidal_delta(vec2(400.0, 595.0), vec2(400.0, 5.0), ARENA);
+        assert_eq!(delta, vec2(0.0, 10.0));
+        assert_eq!(
+            toroidal_distance(vec2(400.0, 5.0), vec2(400.0, 595.0), ARENA),
+            10.0
+        );
+    }
+
+    #[test]
+    fn toroidal_delta_stays_inside_when_that_is_shorter() {
+        let delta = toroidal_delta(vec2(100.0, 100.0), vec2(300.0, 250.0), ARENA);
+        assert_eq!(delta, vec2(200.0, 150.0));
+    }
 }
@@ -4,10 +4,10 @@ use macroquad::prelude::*;
 fn settings() -> Conf {
     Conf {
         window_title: String::from("Asteroids Clone"),
-        window_width: 800,
-        window_height: 800,
+        window_width: SCREEN_WIDTH as i32,
+        window_height: SCREEN_HEIGHT as i32,
         high_dpi: false,
-        window_resizable: false,
+        window_resizable: true,
         fullscreen: false,
         sample_count: 100,
         icon: None,
@@ -15,71 +15,45 @@ fn settings() -> Conf {
     }
 }
 
+/// `--arena <width>x<height>` sets the logical size of the arena, otherwise it's the same as the window
+fn arena_argument() -> Arena {
+    let arguments: Vec<String> = std::env::args().collect();
+    let Some(index) = arguments.iter().position(|argument| argument == "--arena") else {
+        return Arena::default();
+    };
+    let size = arguments.get(index + 1).map_or("", String::as_str);
+    Arena::parse(size).unwrap_or_else(|error| {
+        eprintln!("--arena: {error}. using the default size");
+        Arena::default()
+    })
+}
+
 #[macroquad::main(settings)]
 async fn main() {
     // Ensure random number generation doesn't feel consistent to the player
     initialize_rng();
 
-    // Define game entities
-    let mut player = Player::default();
-    let mut asteroids = Asteroid::many_random(10);
-    let mut bullets = Bullet::many_new();
+    let mixer = Mixer::new(Box::new(MacroquadBackend::load().await));
+    let mut game = Game::with_arena(mixer, arena_argument());
+    if std::env::args().any(|argument| argument == "--debug") {
+        game.set_debug_overlay(true);
+    }
+    // also reads the config for the first time
+    #[cfg(not(target_arch = "wasm32"))]
+    let mut hot_reload = HotReload::new();
+    let mut renderer = MacroquadRenderer;
 
     loop {
-        /* DRAW GAME */
         clear_background(BLACK);
-        player.draw();
-        asteroids.iter().for_each(Asteroid::draw);
-        bullets.iter().for_each(Bullet::draw);
-
-        /* HANDLE INPUT */
-        // Reset asteroids with space for testing
-        if is_key_pressed(KeyCode::Space) {
-            asteroids = Asteroid::many_random(10);
+        // the view is scaled to fit the window however big it is
+        set_camera(&game.world().camera().screen_camera());
+        game.draw(&mut renderer);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            hot_reload.draw(&mut renderer);
+            hot_reload.update(&mut game);
         }
-
-        // fire a bullet with a z press
-        player.handle_input();
-        if is_key_pressed(KeyCode::Z) {
-            bullets.push(Bullet::from(&player));
-        }
-
-        /* COLLISION DETECTION */
-        let mut children = Vec::new();
-        for asteroid in asteroids.iter_mut() {
-            for bullet in bullets.iter_mut() {
-                // if the bullet is inside the asteroid
-                if asteroid.is_point_inside(bullet.position()) {
-                    // calculate the children asteroids
-                    let new_children = asteroid.split(bullet.velocity());
-
-                    // collect the children
-                    children.extend(new_children);
-
-                    // destroy does NOT take ownership it just sets the has_collided field true
-                    asteroid.set_collided();
-                    bullet.set_collided();
-                }
-            }
-            if asteroid.has_collided() {
-                continue;
-            }
-            // TODO: check for collision with player and asteroid and print on all collisions
-        }
-
-        /* HANDLE COLLISION */
-        // add any children from the collisions
-        asteroids.append(&mut children);
-
-        // Only keep bullets and asteroids that are alive or valid.
-        asteroids.retain(Asteroid::is_alive);
-        bullets.retain(Bullet::is_alive);
-        // Is the player alive?
-
-        /* UPDATE GAME PHYSICS */
-        player.step();
-        asteroids.iter_mut().for_each(Asteroid::step);
-        bullets.iter_mut().for_each(Bullet::step);
+        game.update();
 
         next_frame().await;
     }
@@ -0,0 +1,129 @@
+//! The classic two note heartbeat that speeds up as the asteroids are cleared
+//! - both notes are synthesized at runtime, there are no music files
+//! - the tempo depends on how many asteroids are left and how much asteroid area is left, compared to the start of the wave
+
+use crate::{Asteroid, Mixer, SoundEffect};
+use std::{f32::consts::TAU, sync::OnceLock};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heartbeat {
+    /// Number of asteroids when the current wave started
+    wave_count: usize,
+    /// Total asteroid area when the current wave started
+    wave_area: f32,
+    frames_until_beat: usize,
+    is_next_beat_high: bool,
+}
+impl Heartbeat {
+    /// Frames between beats at the start of a wave
+    pub const SLOWEST_INTERVAL: usize = 60;
+    /// Frames between beats when the wave is almost cleared
+    pub const FASTEST_INTERVAL: usize = 12;
+    pub const LOW_FREQUENCY: f32 = 110.0;
+    pub const HIGH_FREQUENCY: f32 = 123.5;
+}
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self {
+            wave_count: 0,
+            wave_area: 0.0,
+            frames_until_beat: 0,
+            is_next_beat_high: false,
+        }
+    }
+}
+impl Heartbeat {
+    /// Remember how much asteroid there was to begin with
+    pub fn start_wave(&mut self, asteroids: &[Asteroid]) {
+        self.wave_count = asteroids.len();
+        self.wave_area = total_area(asteroids);
+        self.frames_until_beat = Self::SLOWEST_INTERVAL;
+    }
+    /// How many frames to wait between beats.
+    /// Fewer asteroids and less remaining area both make the interval shorter.
+    /// Splitting asteroids adds more of them, so the count only matters once it drops below the wave's starting count
+    pub fn interval(&self, asteroids: &[Asteroid]) -> usize {
+        let urgency_of = |remaining: f32, start: f32| {
+            if start > 0.0 {
+                1.0 - (remaining / start).min(1.0)
+            } else {
+                1.0
+            }
+        };
+        let count_urgency = urgency_of(asteroids.len() as f32, self.wave_count as f32);
+        let area_urgency = urgency_of(total_area(asteroids), self.wave_area);
+
+        let urgency = (count_urgency + area_urgency) / 2.0;
+        let slowest = Self::SLOWEST_INTERVAL as f32;
+        let fastest = Self::FASTEST_INTERVAL as f32;
+        (slowest + (fastest - slowest) * urgency).round() as usize
+    }
+    /// Counts down one frame and plays the next note when it is time
+    pub fn step(&mut self, asteroids: &[Asteroid], mixer: &mut Mixer) {
+        if asteroids.is_empty() {
+            return;
+        }
+        self.frames_until_beat = self.frames_until_beat.saturating_sub(1);
+        if self.frames_until_beat > 0 {
+            return;
+        }
+
+        let beat = if self.is_next_beat_high {
+            SoundEffect::BeatHigh
+        } else {
+            SoundEffect::BeatLow
+        };
+        mixer.play(beat);
+        self.is_next_beat_high = !self.is_next_beat_high;
+        self.frames_until_beat = self.interval(asteroids);
+    }
+}
+
+fn total_area(asteroids: &[Asteroid]) -> f32 {
+    asteroids.iter().map(Asteroid::area).sum()
+}
+
+static BEAT_LOW_WAV: OnceLock<Vec<u8>> = OnceLock::new();
+pub fn beat_low_wav() -> &'static [u8] {
+    BEAT_LOW_WAV.get_or_init(|| synthesize_thump(Heartbeat::LOW_FREQUENCY))
+}
+
+static BEAT_HIGH_WAV: OnceLock<Vec<u8>> = OnceLock::new();
+pub fn beat_high_wav() -> &'static [u8] {
+    BEAT_HIGH_WAV.get_or_init(|| synthesize_thump(Heartbeat::HIGH_FREQUENCY))
+}
+
+/// Builds a short, quickly decaying note as a 16 bit mono wav file
+pub fn synthesize_thump(frequency: f32) -> Vec<u8> {
+    const SAMPLE_RATE: u32 = 22050;
+    const DURATION: f32 = 0.12;
+    const DECAY: f32 = 30.0;
+    const VOLUME: f32 = 0.6;
+
+    let sample_count = (SAMPLE_RATE as f32 * DURATION) as u32;
+    let samples = (0..sample_count).map(|i| {
+        let time = i as f32 / SAMPLE_RATE as f32;
+        // a sine with a little of its third harmonic is still audible on small speakers
+        let wave = (TAU * frequency * time).sin() + 0.3 * (3.0 * TAU * frequency * time).sin();
+        let envelope = (-DECAY * time).exp();
+        (wave * envelope * VOLUME / 1.3 * i16::MAX as f32) as i16
+    });
+
+    // https://en.wikipedia.org/wiki/WAV#WAV_file_header
+    let data_size = sample_count * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // format chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // bytes per sample
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+    samples.for_each(|sample| wav.extend_from_slice(&sample.to_le_bytes()));
+    wav
+}
@@ -1,16 +1,22 @@
 use crate::{
-    duck_texture, kinematic::Kinematic, polar_vec2, screen_origin, Draw, KinematicGetters,
-    KinematicMutators, RotationMatrix,
+    polar_vec2, Actions, Arena, Collider, Components, Draw, Entity, GameConfig, Kinematic,
+    KinematicGetters, KinematicMutators, Renderer, RotationMatrix, Sprite, VectorDisplay,
 };
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::f32::consts::{FRAC_PI_2, TAU};
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
-    kinematic: Kinematic,
-    has_collided: bool,
+    components: Components,
     lives: usize,
     orientation: f32,
+    invulnerable_frames: usize,
+    /// Was thrust applied by the last [Self::apply_actions]
+    #[serde(skip)]
+    is_thrusting: bool,
 }
+/// [Self::ROTATION_DELTA], [Self::THRUST] and [Self::MAX_SPEED] are only defaults, see [GameConfig]
 impl Player {
     pub const SIZE: f32 = 20.0;
     /// An angle in radians
@@ -19,6 +25,10 @@ impl Player {
 
     pub const MAX_SPEED: f32 = Self::SIZE * 2.0;
 
+    pub const STARTING_LIVES: usize = 3;
+    /// How long the player can't collide with asteroids after respawning
+    pub const RESPAWN_FRAMES: usize = 120;
+
     pub const VERTICES: [Vec2; 3] = [
         vec2(0.0, Self::SIZE),
         vec2(-Self::SIZE / 2.5, Self::SIZE / -4.0),
@@ -26,17 +36,42 @@ impl Player {
     ];
 }
 impl Player {
-    /// Create a stationary player at the screen's origin
-    pub fn default() -> Player {
+    /// Create a stationary player at the arena's origin, invulnerable for [Self::RESPAWN_FRAMES]
+    pub fn spawn(arena: &Arena) -> Player {
         Player {
-            kinematic: Kinematic::new(screen_origin(), Vec2::ZERO, Vec2::ZERO),
-            lives: 3,
+            invulnerable_frames: Self::RESPAWN_FRAMES,
+            ..Player::new(arena.origin())
+        }
+    }
+    /// Create a stationary player at `position` that can be hit right away
+    pub fn new(position: Vec2) -> Player {
+        Player {
+            components: Self::components_at(position),
+            lives: Self::STARTING_LIVES,
             orientation: 0.0,
-            has_collided: false,
+            invulnerable_frames: 0,
+            is_thrusting: false,
         }
     }
-    pub fn has_collided(&self) -> bool {
-        self.has_collided
+    /// Stationary at `position`, and not collided
+    fn components_at(position: Vec2) -> Components {
+        let kinematic = Kinematic::new(position, Vec2::ZERO, Vec2::ZERO);
+        // the front vertex is the furthest from the center, at `SIZE`
+        Components::new(kinematic, Collider::new(Self::SIZE))
+    }
+    pub fn lives(&self) -> usize {
+        self.lives
+    }
+    pub fn is_invulnerable(&self) -> bool {
+        self.invulnerable_frames > 0
+    }
+    pub fn is_thrusting(&self) -> bool {
+        self.is_thrusting
+    }
+    /// The player blinks while invulnerable. Is this one of the frames where it isn't drawn?
+    pub fn is_blinked_out(&self) -> bool {
+        const BLINK_FRAMES: usize = 8;
+        (self.invulnerable_frames / BLINK_FRAMES) % 2 == 1
     }
     /// Getter for the player's orientation angle
     pub fn orientation(&self) -> f32 {
@@ -45,7 +80,7 @@ impl Player {
     /// Returns vertices of player by calculating default vertices rotated by `self.orientation` then translated by `self.position`
     pub fn vertices(&self) -> [Vec2; 3] {
         let rotation = self.orientation.rotation_matrix();
-        let position = self.kinematic.position();
+        let position = self.position();
 
         let vertices = Self::VERTICES.map(|vertex| (rotation * vertex) + position);
 
@@ -53,77 +88,84 @@ impl Player {
     }
     pub fn front_vertex(&self) -> Vec2 {
         let rotation = self.orientation.rotation_matrix();
-        let position = self.kinematic.position();
+        let position = self.position();
 
         let front_vertex = rotation * Self::VERTICES[0] + position;
 
         front_vertex
     }
 }
-impl KinematicGetters for Player {
-    fn kinematic(&self) -> &Kinematic {
-        &self.kinematic
+impl Entity for Player {
+    fn components(&self) -> &Components {
+        &self.components
     }
-}
-impl KinematicMutators for Player {
-    fn kinematic_mut(&mut self) -> &mut Kinematic {
-        &mut self.kinematic
+    fn components_mut(&mut self) -> &mut Components {
+        &mut self.components
     }
 }
 impl Player {
-    pub fn destroy(&mut self) {
-        self.has_collided = true;
+    pub fn gain_life(&mut self) {
+        self.lives += 1;
+    }
+    pub fn set_lives(&mut self, lives: usize) {
+        self.lives = lives;
+    }
+    /// Take away one life and put the player back at the arena's origin, invulnerable for [Self::RESPAWN_FRAMES]
+    pub fn lose_life(&mut self, arena: &Arena) {
+        self.lives = self.lives.saturating_sub(1);
+        self.components = Self::components_at(arena.origin());
+        self.orientation = 0.0;
+        self.invulnerable_frames = Self::RESPAWN_FRAMES;
     }
     /// - rotate
-    ///   - Left (counter-clockwise)
-    ///   - Right (clockwise)
+    ///   - left (counter-clockwise)
+    ///   - right (clockwise)
     ///   - -2pi <= 'self.orientation' <= 2pi
     /// - accelerate player forward
-    ///   - Up
-    pub fn handle_input(&mut self) {
-        if is_key_down(KeyCode::Left) {
-            self.orientation -= Self::ROTATION_DELTA;
+    ///   - thrust
+    pub fn apply_actions(&mut self, actions: &Actions, config: &GameConfig) {
+        if actions.rotate_left {
+            self.orientation -= config.player.rotation_delta;
         }
-        if is_key_down(KeyCode::Right) {
-            self.orientation += Self::ROTATION_DELTA;
+        if actions.rotate_right {
+            self.orientation += config.player.rotation_delta;
         }
         self.orientation %= TAU;
 
-        if is_key_down(KeyCode::Up) {
-            let thrust = polar_vec2(Self::THRUST, self.orientation);
+        self.is_thrusting = actions.thrust;
+        if self.is_thrusting {
+            let thrust = polar_vec2(config.player.thrust, self.orientation);
             self.apply_acceleration(thrust)
         }
     }
     /// Move one time step further in the player simulation
-    pub fn step(&mut self) {
-        self.cap_speed(Self::MAX_SPEED);
-        self.keep_on_screen();
-        self.step_motion();
-        self.step_friction();
+    pub fn step(&mut self, arena: &Arena, config: &GameConfig) {
+        self.cap_speed(config.player.max_speed);
+        self.step_components(arena);
+        self.step_friction(config.player.friction);
+        self.invulnerable_frames = self.invulnerable_frames.saturating_sub(1);
     }
 }
 impl Draw for Player {
-    fn draw(&self) {
-        let position = self.position();
-        let [v1, v2, v3] = self.vertices();
-        draw_triangle(v1, v2, v3, WHITE);
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        if self.is_blinked_out() {
+            return;
+        }
 
-        const TEXTURE_OFFSET: f32 = Player::SIZE / 2.0;
-        let texture_position = position - TEXTURE_OFFSET;
-        draw_texture_ex(
-            duck_texture(),
-            texture_position.x,
-            texture_position.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(Vec2::splat(Self::SIZE)),
-                source: None,
-                rotation: self.orientation - FRAC_PI_2,
-                flip_x: false,
-                flip_y: true,
-                pivot: None,
-            },
+        let position = self.position();
+        renderer.draw_polygon(&self.vertices(), WHITE);
+        renderer.draw_sprite(
+            Sprite::Duck,
+            position,
+            Self::SIZE,
+            self.orientation - FRAC_PI_2,
         );
-        draw_circle(position.x, position.y, 2.5, RED);
+        renderer.draw_circle(position, 2.5, RED);
+    }
+    fn draw_outline(&self, renderer: &mut dyn Renderer) {
+        if self.is_blinked_out() {
+            return;
+        }
+        renderer.draw_polygon_lines(&self.vertices(), VectorDisplay::LINE_THICKNESS, WHITE);
     }
 }
@@ -0,0 +1,235 @@
+//! Everything that gets drawn goes through a [Renderer] instead of calling macroquad directly
+//! - [MacroquadRenderer] draws to the window and is what the game uses
+//! - [RecordingRenderer] just remembers every call, so a frame can be inspected or snapshotted without a window
+
+use crate::{duck_texture, rock_texture};
+use macroquad::prelude::*;
+
+/// The textures that can be drawn with [Renderer::draw_sprite]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sprite {
+    Duck,
+    Rock,
+}
+
+pub trait Renderer {
+    /// A filled convex polygon
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color);
+    /// Only the edges of a closed polygon
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color);
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color);
+    /// A square `sprite` centered on `center`, rotated by `rotation` radians
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32);
+    /// `position` is the left end of the text's baseline
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color);
+    /// The width and height `text` would take up if drawn
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2;
+}
+
+pub struct MacroquadRenderer;
+impl Renderer for MacroquadRenderer {
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color) {
+        // a fan of triangles from the first vertex covers any convex polygon
+        if let Some((&first, rest)) = vertices.split_first() {
+            for edge in rest.windows(2) {
+                draw_triangle(first, edge[0], edge[1], color);
+            }
+        }
+    }
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color) {
+        for (i, &start) in vertices.iter().enumerate() {
+            let end = vertices[(i + 1) % vertices.len()];
+            draw_line(start.x, start.y, end.x, end.y, thickness, color);
+        }
+    }
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        draw_circle(center.x, center.y, radius, color);
+    }
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32) {
+        let texture = match sprite {
+            Sprite::Duck => duck_texture(),
+            Sprite::Rock => rock_texture(),
+        };
+        let top_left = center - size / 2.0;
+        draw_texture_ex(
+            &texture,
+            top_left.x,
+            top_left.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::splat(size)),
+                source: None,
+                rotation,
+                flip_x: false,
+                flip_y: true,
+                pivot: None,
+            },
+        );
+    }
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        draw_text(text, position.x, position.y, font_size, color);
+    }
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        let dimensions = measure_text(text, None, font_size as u16, 1.0);
+        vec2(dimensions.width, dimensions.height)
+    }
+}
+
+/// One call made to a [RecordingRenderer]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Polygon {
+        vertices: Vec<Vec2>,
+        color: Color,
+    },
+    PolygonLines {
+        vertices: Vec<Vec2>,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Color,
+    },
+    Sprite {
+        sprite: Sprite,
+        center: Vec2,
+        size: f32,
+        rotation: f32,
+    },
+    Text {
+        text: String,
+        position: Vec2,
+        font_size: f32,
+        color: Color,
+    },
+}
+
+#[derive(Debug, Default)]
+pub struct RecordingRenderer {
+    calls: Vec<DrawCall>,
+}
+impl RecordingRenderer {
+    /// Every character is assumed to be this fraction of the font size wide
+    pub const CHARACTER_WIDTH: f32 = 0.5;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn calls(&self) -> &[DrawCall] {
+        &self.calls
+    }
+    /// Returns every call recorded so far and starts over
+    pub fn take(&mut self) -> Vec<DrawCall> {
+        std::mem::take(&mut self.calls)
+    }
+}
+impl Renderer for RecordingRenderer {
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color) {
+        self.calls.push(DrawCall::Polygon {
+            vertices: vertices.to_vec(),
+            color,
+        });
+    }
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color) {
+        self.calls.push(DrawCall::PolygonLines {
+            vertices: vertices.to_vec(),
+            thickness,
+            color,
+        });
+    }
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.calls.push(DrawCall::Circle {
+            center,
+            radius,
+            color,
+        });
+    }
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32) {
+        self.calls.push(DrawCall::Sprite {
+            sprite,
+            center,
+            size,
+            rotation,
+        });
+    }
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        self.calls.push(DrawCall::Text {
+            text: text.to_string(),
+            position,
+            font_size,
+            color,
+        });
+    }
+    /// Doesn't need any fonts, so the result is only an estimate
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        let width = text.chars().count() as f32 * font_size * Self::CHARACTER_WIDTH;
+        vec2(width, font_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Asteroid, Draw, Player};
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn player_snapshot() {
+        let player = Player::new(vec2(50.0, 50.0));
+        let mut renderer = RecordingRenderer::new();
+        player.draw(&mut renderer);
+        assert_eq!(
+            renderer.take(),
+            [
+                DrawCall::Polygon {
+                    vertices: player.vertices().to_vec(),
+                    color: WHITE,
+                },
+                DrawCall::Sprite {
+                    sprite: Sprite::Duck,
+                    center: vec2(50.0, 50.0),
+                    size: Player::SIZE,
+                    rotation: -FRAC_PI_2,
+                },
+                DrawCall::Circle {
+                    center: vec2(50.0, 50.0),
+                    radius: 2.5,
+                    color: RED,
+                },
+            ]
+        );
+        // facing right, with the front vertex `SIZE` ahead
+        assert_eq!(player.vertices()[0], vec2(70.0, 50.0));
+    }
+
+    #[test]
+    fn asteroid_snapshot() {
+        let asteroid = Asteroid::new(vec2(100.0, 100.0), Vec2::ZERO, 60.0, 0.0, 0.0);
+        let mut renderer = RecordingRenderer::new();
+        asteroid.draw(&mut renderer);
+        asteroid.draw_outline(&mut renderer);
+        assert_eq!(
+            renderer.take(),
+            [
+                DrawCall::Polygon {
+                    vertices: asteroid.vertices().to_vec(),
+                    color: WHITE,
+                },
+                DrawCall::Sprite {
+                    sprite: Sprite::Rock,
+                    center: vec2(100.0, 100.0),
+                    size: 60.0 * 1.4,
+                    rotation: -FRAC_PI_2,
+                },
+                DrawCall::PolygonLines {
+                    vertices: asteroid.vertices().to_vec(),
+                    thickness: crate::VectorDisplay::LINE_THICKNESS,
+                    color: WHITE,
+                },
+            ]
+        );
+        assert!(renderer.calls().is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+//! A [Renderer] that draws into an RGBA buffer on the cpu, so frames can be rendered without a window or gpu
+//! - polygons and circles are filled by testing the center of every pixel they might cover
+//! - sprites are sampled from [duck_image] and [rock_image], nearest pixel, with the same rotation and flip as macroquad
+//! - text uses a small built in 5x7 bitmap font instead of macroquad's fonts
+//! - frames can be saved to and loaded from png, and compared to each other for golden image tests
+
+use crate::{bounding_box, duck_image, is_point_in_polygon, rock_image, Renderer, Sprite};
+use macroquad::prelude::*;
+use std::path::Path;
+
+pub struct SoftwareRenderer {
+    /// Rows are stored top to bottom
+    frame: Image,
+}
+impl SoftwareRenderer {
+    /// Glyphs are 5 font pixels wide and 7 tall
+    const GLYPH_WIDTH: usize = 5;
+    const GLYPH_HEIGHT: usize = 7;
+    /// Space between the start of two characters, in font pixels
+    const GLYPH_ADVANCE: usize = Self::GLYPH_WIDTH + 1;
+    /// How many screen pixels one font pixel is at a font size of 1
+    const FONT_SCALE: f32 = 0.1;
+}
+impl SoftwareRenderer {
+    /// A black frame
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            frame: Image::gen_image_color(width, height, BLACK),
+        }
+    }
+    pub fn frame(&self) -> &Image {
+        &self.frame
+    }
+    pub fn width(&self) -> usize {
+        self.frame.width()
+    }
+    pub fn height(&self) -> usize {
+        self.frame.height()
+    }
+    pub fn clear(&mut self, color: Color) {
+        let color: [u8; 4] = color.into();
+        self.frame.get_image_data_mut().fill(color);
+    }
+    pub fn save_png(&self, path: impl AsRef<Path>) -> image::ImageResult<()> {
+        save_png(&self.frame, path)
+    }
+}
+impl SoftwareRenderer {
+    /// Blends `color` over the pixel at `(x, y)`, if it is inside the frame
+    fn blend_pixel(&mut self, x: i64, y: i64, color: Color) {
+        if x < 0 || y < 0 || x >= self.width() as i64 || y >= self.height() as i64 {
+            return;
+        }
+        let index = y as usize * self.width() + x as usize;
+        let pixel = &mut self.frame.get_image_data_mut()[index];
+        let destination = Color::from_rgba(pixel[0], pixel[1], pixel[2], pixel[3]);
+        let alpha = color.a;
+        let blended = Color::new(
+            color.r * alpha + destination.r * (1.0 - alpha),
+            color.g * alpha + destination.g * (1.0 - alpha),
+            color.b * alpha + destination.b * (1.0 - alpha),
+            alpha + destination.a * (1.0 - alpha),
+        );
+        *pixel = blended.into();
+    }
+    /// Calls `is_covered` with the center of every pixel between `min` and `max`, and blends `color` where it returns true
+    fn fill(&mut self, min: Vec2, max: Vec2, color: Color, is_covered: impl Fn(Vec2) -> bool) {
+        let x_range =
+            (min.x.floor().max(0.0) as i64)..(max.x.ceil().min(self.width() as f32) as i64);
+        let y_range =
+            (min.y.floor().max(0.0) as i64)..(max.y.ceil().min(self.height() as f32) as i64);
+        for y in y_range {
+            for x in x_range.clone() {
+                let center = vec2(x as f32 + 0.5, y as f32 + 0.5);
+                if is_covered(center) {
+                    self.blend_pixel(x, y, color);
+                }
+            }
+        }
+    }
+    fn draw_line(&mut self, start: Vec2, end: Vec2, thickness: f32, color: Color) {
+        let radius = thickness / 2.0;
+        let min = start.min(end) - radius;
+        let max = start.max(end) + radius;
+        self.fill(min, max, color, |point| {
+            distance_to_segment(point, start, end) <= radius
+        });
+    }
+}
+impl Renderer for SoftwareRenderer {
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color) {
+        let Some((min, max)) = bounding_box(vertices) else {
+            return;
+        };
+        self.fill(min, max, color, |point| {
+            is_point_in_polygon(point, vertices)
+        });
+    }
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color) {
+        for (i, &start) in vertices.iter().enumerate() {
+            let end = vertices[(i + 1) % vertices.len()];
+            self.draw_line(start, end, thickness, color);
+        }
+    }
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.fill(center - radius, center + radius, color, |point| {
+            point.distance(center) <= radius
+        });
+    }
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32) {
+        let image = match sprite {
+            Sprite::Duck => duck_image(),
+            Sprite::Rock => rock_image(),
+        };
+        // the rotated square fits inside a circle through its corners
+        let reach = size * std::f32::consts::FRAC_1_SQRT_2;
+        let unrotate = Mat2::from_angle(-rotation);
+        let x_range = ((center.x - reach).floor() as i64)..((center.x + reach).ceil() as i64);
+        let y_range = ((center.y - reach).floor() as i64)..((center.y + reach).ceil() as i64);
+        for y in y_range {
+            for x in x_range.clone() {
+                let pixel_center = vec2(x as f32 + 0.5, y as f32 + 0.5);
+                let local = unrotate * (pixel_center - center) / size + 0.5;
+                if local.cmplt(Vec2::ZERO).any() || local.cmpge(Vec2::ONE).any() {
+                    continue;
+                }
+                // macroquad draws sprites with flip_y
+                let u = (local.x * image.width() as f32) as u32;
+                let v = ((1.0 - local.y) * image.height() as f32) as u32;
+                let v = v.min(image.height() as u32 - 1);
+                self.blend_pixel(x, y, image.get_pixel(u, v));
+            }
+        }
+    }
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        let scale = font_size * Self::FONT_SCALE;
+        let top = position.y - Self::GLYPH_HEIGHT as f32 * scale;
+        for (i, character) in text.chars().enumerate() {
+            let left = position.x + (i * Self::GLYPH_ADVANCE) as f32 * scale;
+            for (row, bits) in glyph(character).into_iter().enumerate() {
+                for column in 0..Self::GLYPH_WIDTH {
+                    if bits & (1 << (Self::GLYPH_WIDTH - 1 - column)) == 0 {
+                        continue;
+                    }
+                    let min = vec2(left + column as f32 * scale, top + row as f32 * scale);
+                    self.fill(min, min + scale, color, |_| true);
+                }
+            }
+        }
+    }
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        let scale = font_size * Self::FONT_SCALE;
+        let count = text.chars().count();
+        let width = (count * Self::GLYPH_ADVANCE).saturating_sub(1) as f32 * scale;
+        vec2(width, Self::GLYPH_HEIGHT as f32 * scale)
+    }
+}
+
+fn distance_to_segment(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let segment = end - start;
+    let length_squared = segment.length_squared();
+    if length_squared == 0.0 {
+        return point.distance(start);
+    }
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    point.distance(start + segment * t)
+}
+
+/// Rows of a 5x7 glyph, top to bottom. The highest of the 5 bits is the leftmost pixel.
+/// Lowercase letters are drawn as uppercase and unknown characters as `?`
+fn glyph(character: char) -> [u8; 7] {
+    match character.to_ascii_uppercase() {
+        ' ' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
+
+/// Writes `frame` to a png file. Rows are expected top to bottom
+pub fn save_png(frame: &Image, path: impl AsRef<Path>) -> image::ImageResult<()> {
+    image::save_buffer(
+        path,
+        &frame.bytes,
+        frame.width as u32,
+        frame.height as u32,
+        image::ColorType::Rgba8,
+    )
+}
+
+pub fn load_png(path: impl AsRef<Path>) -> image::ImageResult<Image> {
+    let decoded = image::open(path)?.to_rgba8();
+    Ok(Image {
+        width: decoded.width() as u16,
+        height: decoded.height() as u16,
+        bytes: decoded.into_raw(),
+    })
+}
+
+/// Counts the pixels where any channel differs by more than `tolerance`.
+/// Returns [None] if the images aren't the same size
+pub fn count_different_pixels(a: &Image, b: &Image, tolerance: u8) -> Option<usize> {
+    if a.width != b.width || a.height != b.height {
+        return None;
+    }
+    let count = a
+        .get_image_data()
+        .iter()
+        .zip(b.get_image_data())
+        .filter(|(a, b)| {
+            a.iter()
+                .zip(b.iter())
+                .any(|(a, b)| a.abs_diff(*b) > tolerance)
+        })
+        .count();
+    Some(count)
+}
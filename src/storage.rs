@@ -0,0 +1,45 @@
+//! Persist small strings between runs of the game
+//! - native: one file per key in the user's data directory
+//! - wasm: the browser's local storage (needs `quad-storage.js` loaded in `index.html`)
+
+use std::io;
+
+#[cfg(not(target_arch = "wasm32"))]
+const APP_DIRECTORY: &str = "asteroids_clone";
+
+/// Returns the path of the file backing `key`, or [None] if the platform has no data directory
+#[cfg(not(target_arch = "wasm32"))]
+pub fn path(key: &str) -> Option<std::path::PathBuf> {
+    dirs::data_dir().map(|directory| directory.join(APP_DIRECTORY).join(key))
+}
+
+/// Returns the value saved under `key`, or [None] if nothing could be read
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(path(key)?).ok()
+}
+
+/// Saves `value` under `key`, replacing any previous value
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) -> io::Result<()> {
+    let path = path(key).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "could not find a data directory")
+    })?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    std::fs::write(path, value)
+}
+
+/// Returns the value saved under `key`, or [None] if nothing could be read
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    quad_storage_sys::get(&format!("{}/{}", env!("CARGO_PKG_NAME"), key))
+}
+
+/// Saves `value` under `key`, replacing any previous value
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) -> io::Result<()> {
+    quad_storage_sys::set(&format!("{}/{}", env!("CARGO_PKG_NAME"), key), value);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Slow motion, fast forward and frame by frame stepping, for looking at collisions closely
+//! - the world always moves in the same fixed steps, the time scale only changes how many happen per frame
+//! - an accumulator collects the scale every frame and a step is taken for every whole step in it,
+//!   so 0.25 steps every fourth frame and 4 steps four times every frame
+//! - frozen: no steps at all, except the ones asked for with [TimeControl::advance]
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeControl {
+    scale: f32,
+    /// Steps owed but not taken yet, always less than one after [Self::steps_this_frame]
+    accumulator: f32,
+    is_frozen: bool,
+    /// Steps asked for while frozen
+    frames_to_advance: usize,
+}
+impl TimeControl {
+    pub const MIN_SCALE: f32 = 0.1;
+    pub const MAX_SCALE: f32 = 4.0;
+    /// What [Self::slower] and [Self::faster] go through
+    pub const SCALES: [f32; 6] = [0.1, 0.25, 0.5, 1.0, 2.0, 4.0];
+}
+impl Default for TimeControl {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            accumulator: 0.0,
+            is_frozen: false,
+            frames_to_advance: 0,
+        }
+    }
+}
+impl TimeControl {
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+    /// Clamped between [Self::MIN_SCALE] and [Self::MAX_SCALE]. Ignored if it isn't a finite number
+    pub fn set_scale(&mut self, scale: f32) {
+        if !scale.is_finite() {
+            return;
+        }
+        self.scale = scale.clamp(Self::MIN_SCALE, Self::MAX_SCALE);
+    }
+    /// The next smaller of [Self::SCALES]
+    pub fn slower(&mut self) {
+        if let Some(&scale) = Self::SCALES.iter().rev().find(|&&scale| scale < self.scale) {
+            self.scale = scale;
+        }
+    }
+    /// The next bigger of [Self::SCALES]
+    pub fn faster(&mut self) {
+        if let Some(&scale) = Self::SCALES.iter().find(|&&scale| scale > self.scale) {
+            self.scale = scale;
+        }
+    }
+    pub fn is_frozen(&self) -> bool {
+        self.is_frozen
+    }
+    pub fn set_frozen(&mut self, is_frozen: bool) {
+        self.is_frozen = is_frozen;
+        self.accumulator = 0.0;
+        self.frames_to_advance = 0;
+    }
+    /// Take `frames` more steps while frozen, one per frame
+    pub fn advance(&mut self, frames: usize) {
+        self.frames_to_advance += frames;
+    }
+    /// How many steps the world should take this frame. Call exactly once per frame
+    pub fn steps_this_frame(&mut self) -> usize {
+        if self.is_frozen {
+            let steps = self.frames_to_advance.min(1);
+            self.frames_to_advance -= steps;
+            return steps;
+        }
+        self.accumulator += self.scale;
+        let steps = self.accumulator.floor();
+        self.accumulator -= steps;
+        steps as usize
+    }
+}
@@ -0,0 +1,141 @@
+//! An alternative "vector monitor" look, toggled at runtime
+//! - [Draw::draw_outline] draws only the outlines of things, without any sprites
+//! - outlines go into a phosphor buffer that only fades a little every frame instead of being cleared, leaving trails
+//! - the buffer is drawn to the screen, then drawn again blurred with additive blending to make it glow
+
+use crate::{Draw, GameCamera, Renderer};
+use macroquad::{
+    miniquad::{BlendFactor, BlendState, Equation, PipelineParams, UniformDesc, UniformType},
+    prelude::*,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RenderMode {
+    /// Filled shapes with textures
+    #[default]
+    Sprite,
+    /// Glowing outlines
+    Vector,
+}
+impl RenderMode {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Sprite => Self::Vector,
+            Self::Vector => Self::Sprite,
+        }
+    }
+}
+
+pub struct VectorDisplay {
+    phosphor: RenderTarget,
+    glow: Material,
+}
+impl VectorDisplay {
+    pub const LINE_THICKNESS: f32 = 2.0;
+    /// How much of the last frame is still visible in the next one
+    pub const PERSISTENCE: f32 = 0.6;
+    /// How bright the blurred glow is compared to the sharp lines
+    pub const GLOW_STRENGTH: f32 = 1.5;
+    /// How far apart the blur samples are, in pixels
+    pub const GLOW_SPREAD: f32 = 2.0;
+
+    const VERTEX_SHADER: &'static str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+
+varying lowp vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}
+"#;
+
+    /// Weighted average of the surrounding 9x9 pixels
+    const GLOW_FRAGMENT_SHADER: &'static str = r#"#version 100
+precision mediump float;
+
+varying lowp vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 texel_size;
+uniform float strength;
+
+void main() {
+    vec3 sum = vec3(0.0);
+    float total_weight = 0.0;
+    for (int x = -4; x <= 4; x++) {
+        for (int y = -4; y <= 4; y++) {
+            float weight = 1.0 / (1.0 + float(x * x + y * y));
+            vec2 offset = vec2(float(x), float(y)) * texel_size;
+            sum += texture2D(Texture, uv + offset).rgb * weight;
+            total_weight += weight;
+        }
+    }
+    gl_FragColor = vec4(sum / total_weight * strength, 1.0);
+}
+"#;
+}
+impl VectorDisplay {
+    /// The phosphor is the same size as the `camera`'s view.
+    /// Needs a graphics context, so this can only be called once the window is open
+    pub fn new(camera: &GameCamera) -> Self {
+        let size = camera.view();
+        let phosphor = render_target(size.x as u32, size.y as u32);
+        phosphor.texture.set_filter(FilterMode::Linear);
+
+        let additive = BlendState::new(Equation::Add, BlendFactor::One, BlendFactor::One);
+        let glow = load_material(
+            ShaderSource::Glsl {
+                vertex: Self::VERTEX_SHADER,
+                fragment: Self::GLOW_FRAGMENT_SHADER,
+            },
+            MaterialParams {
+                pipeline_params: PipelineParams {
+                    color_blend: Some(additive),
+                    ..Default::default()
+                },
+                uniforms: vec![
+                    UniformDesc::new("texel_size", UniformType::Float2),
+                    UniformDesc::new("strength", UniformType::Float1),
+                ],
+                ..Default::default()
+            },
+        )
+        .expect("glow shader should compile");
+
+        Self { phosphor, glow }
+    }
+    /// Fades the phosphor, draws the outline of `scene` into it, then draws the phosphor and its glow to the screen.
+    /// `renderer` has to draw with macroquad so it ends up in the phosphor. Leaves the `camera`'s [GameCamera::screen_camera] set
+    pub fn draw(&self, scene: &impl Draw, camera: &GameCamera, renderer: &mut dyn Renderer) {
+        let texture = &self.phosphor.texture;
+        let size = texture.size();
+
+        let mut phosphor_camera = Camera2D::from_display_rect(Rect::new(0.0, 0.0, size.x, size.y));
+        phosphor_camera.render_target = Some(self.phosphor.clone());
+        set_camera(&phosphor_camera);
+        let fade = Color::new(0.0, 0.0, 0.0, 1.0 - Self::PERSISTENCE);
+        draw_rectangle(0.0, 0.0, size.x, size.y, fade);
+        scene.draw_outline(renderer);
+        set_camera(&camera.screen_camera());
+
+        // render targets come out upside down
+        let params = DrawTextureParams {
+            dest_size: Some(camera.view()),
+            flip_y: true,
+            ..Default::default()
+        };
+        draw_texture_ex(texture, 0.0, 0.0, WHITE, params.clone());
+
+        self.glow
+            .set_uniform("texel_size", Self::GLOW_SPREAD / size);
+        self.glow.set_uniform("strength", Self::GLOW_STRENGTH);
+        gl_use_material(&self.glow);
+        draw_texture_ex(texture, 0.0, 0.0, WHITE, params);
+        gl_use_default_material();
+    }
+}
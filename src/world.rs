@@ -0,0 +1,562 @@
+//! The game simulation, without any knowledge of menus or pausing
+//! - owns the player, asteroids and bullets
+//! - checks collisions between them
+//! - plays by a [GameConfig], which isn't saved since it belongs to whoever is playing and not the game
+//! - keeps track of the score and the current wave
+//! - queues a [GameEvent] for everything that happens, see [crate::event]
+//! - can be saved and loaded back exactly to resume a game later
+
+use crate::{
+    storage, Actions, Arena, Asteroid, Bullet, CameraMode, Draw, Entity, GameCamera, GameConfig,
+    GameEvent, KinematicGetters, KinematicMutators, Player, Renderer, SpatialGrid,
+    WrappingRenderer,
+};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct World {
+    arena: Arena,
+    camera: GameCamera,
+    player: Player,
+    asteroids: Vec<Asteroid>,
+    bullets: Vec<Bullet>,
+    score: usize,
+    wave: usize,
+    #[serde(skip)]
+    config: GameConfig,
+    /// Asteroids pass through the player, for testing
+    #[serde(skip)]
+    is_god_mode: bool,
+    /// Not taken by [Self::take_events] yet
+    #[serde(skip)]
+    events: Vec<GameEvent>,
+}
+impl World {
+    pub const INITIAL_ASTEROID_COUNT: usize = 4;
+    /// Asteroids in a new wave will not spawn closer than this to the player
+    pub const SAFE_SPAWN_DISTANCE: f32 = Asteroid::MAX_SIZE * 2.0;
+    /// About the size of the biggest asteroid, so most asteroids only touch a few cells
+    pub const GRID_CELL_SIZE: f32 = Asteroid::MAX_SIZE * 2.0;
+    /// The player gets another life every time the score passes a multiple of this
+    pub const EXTRA_LIFE_SCORE: usize = 10_000;
+
+    /// How many views wide and tall the arena is in [Self::new_large]
+    pub const LARGE_WORLD_SCALE: f32 = 4.0;
+    /// The minimap's width and height in a large world
+    pub const MINIMAP_SIZE: f32 = 160.0;
+    /// How far down the view the score, lives and wave go, so other text can be drawn below them
+    pub const HUD_HEIGHT: f32 = Self::HUD_FONT_SIZE * 3.0;
+    const HUD_FONT_SIZE: f32 = 32.0;
+
+    /// Bump whenever the saved layout of [World] changes, once saves are out in the wild
+    pub const SAVE_VERSION: u32 = 1;
+    const SAVE_KEY: &'static str = "saved_game.json";
+}
+
+/// What actually gets written to storage. `W` is `&World` when saving and `World` when loading
+#[derive(Serialize, Deserialize)]
+struct SaveFile<W> {
+    version: u32,
+    world: W,
+}
+impl Default for World {
+    fn default() -> Self {
+        Self::new(Arena::default())
+    }
+}
+impl World {
+    /// Shows the whole `arena` at once
+    pub fn new(arena: Arena) -> Self {
+        Self::with_camera(arena, GameCamera::fixed(&arena))
+    }
+    /// An arena [Self::LARGE_WORLD_SCALE] times as wide and tall as `view`, with the camera following the player
+    pub fn new_large(view: Vec2) -> Self {
+        let dimensions = view * Self::LARGE_WORLD_SCALE;
+        let arena = Arena::new(dimensions.x, dimensions.y);
+        Self::with_camera(arena, GameCamera::following(view, arena.origin()))
+    }
+    pub fn with_camera(arena: Arena, camera: GameCamera) -> Self {
+        let mut world = Self {
+            arena,
+            camera,
+            player: Player::spawn(&arena),
+            asteroids: Vec::new(),
+            bullets: Bullet::many_new(),
+            score: 0,
+            wave: 0,
+            config: GameConfig::default(),
+            is_god_mode: false,
+            events: Vec::new(),
+        };
+        world.start_next_wave();
+        world
+    }
+    /// A world in its first wave with exactly these things in it, instead of random asteroids
+    pub fn from_parts(
+        arena: Arena,
+        player: Player,
+        asteroids: Vec<Asteroid>,
+        bullets: Vec<Bullet>,
+    ) -> Self {
+        Self {
+            arena,
+            camera: GameCamera::fixed(&arena),
+            player,
+            asteroids,
+            bullets,
+            score: 0,
+            wave: 1,
+            config: GameConfig::default(),
+            is_god_mode: false,
+            events: Vec::new(),
+        }
+    }
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+    pub fn camera(&self) -> &GameCamera {
+        &self.camera
+    }
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+    /// Takes effect from the next step, without touching anything already in the world
+    pub fn set_config(&mut self, config: GameConfig) {
+        self.config = config;
+    }
+    pub fn player(&self) -> &Player {
+        &self.player
+    }
+    pub fn asteroids(&self) -> &[Asteroid] {
+        &self.asteroids
+    }
+    pub fn bullets(&self) -> &[Bullet] {
+        &self.bullets
+    }
+    pub fn score(&self) -> usize {
+        self.score
+    }
+    pub fn wave(&self) -> usize {
+        self.wave
+    }
+    pub fn is_over(&self) -> bool {
+        self.player.lives() == 0
+    }
+}
+impl World {
+    /// Is there a saved game to resume?
+    pub fn has_save() -> bool {
+        storage::load(Self::SAVE_KEY).is_some()
+    }
+    /// Loads the saved game, or [None] if it is missing, broken or outdated
+    pub fn load() -> Option<Self> {
+        Self::from_json(&storage::load(Self::SAVE_KEY)?)
+    }
+    pub fn save(&self) -> io::Result<()> {
+        storage::save(Self::SAVE_KEY, &self.to_json())
+    }
+    /// Returns [None] if `json` isn't a save with the current [Self::SAVE_VERSION]
+    pub fn from_json(json: &str) -> Option<Self> {
+        let file = serde_json::from_str::<SaveFile<World>>(json).ok()?;
+        if file.version != Self::SAVE_VERSION {
+            return None;
+        }
+        Some(file.world)
+    }
+    pub fn to_json(&self) -> String {
+        let file = SaveFile {
+            version: Self::SAVE_VERSION,
+            world: self,
+        };
+        serde_json::to_string(&file).expect("the world is always serializable")
+    }
+}
+impl World {
+    /// Spawns `INITIAL_ASTEROID_COUNT + wave` asteroids for every view's worth of arena, none of them on top of the player
+    pub fn start_next_wave(&mut self) {
+        self.wave += 1;
+        let views = self.arena.dimensions() / self.camera.view();
+        let views = (views.x * views.y).round().max(1.0) as usize;
+        let count = (Self::INITIAL_ASTEROID_COUNT + self.wave - 1) * views;
+        self.asteroids = Asteroid::many_random_away_from(
+            count,
+            self.player.position(),
+            Self::SAFE_SPAWN_DISTANCE,
+            &self.arena,
+        );
+    }
+    /// `count` more random asteroids, all at `position` if there is one
+    pub fn spawn_asteroids(&mut self, count: usize, position: Option<Vec2>) {
+        for _ in 0..count {
+            let mut asteroid = Asteroid::random(&self.arena);
+            if let Some(position) = position {
+                *asteroid.position_mut() = position;
+            }
+            self.asteroids.push(asteroid);
+        }
+    }
+    pub fn set_lives(&mut self, lives: usize) {
+        self.player.set_lives(lives);
+    }
+    pub fn is_god_mode(&self) -> bool {
+        self.is_god_mode
+    }
+    pub fn set_god_mode(&mut self, is_god_mode: bool) {
+        self.is_god_mode = is_god_mode;
+    }
+    /// The broad-phase for collisions, with every asteroid inserted by its index
+    pub fn asteroid_grid(&self) -> SpatialGrid {
+        let mut grid = SpatialGrid::new(&self.arena, Self::GRID_CELL_SIZE);
+        for (index, asteroid) in self.asteroids.iter().enumerate() {
+            grid.insert(index, asteroid.position(), asteroid.collider().radius());
+        }
+        grid
+    }
+    /// Everything that happened since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<GameEvent> {
+        std::mem::take(&mut self.events)
+    }
+    /// What the ship does this step, from whichever [crate::Controller] is flying it
+    pub fn apply_actions(&mut self, actions: Actions) {
+        self.player.apply_actions(&actions, &self.config);
+        if actions.fire {
+            let bullet = Bullet::fired_by(&self.player, &self.config);
+            self.events.push(GameEvent::BulletFired {
+                position: bullet.position(),
+            });
+            self.bullets.push(bullet);
+        }
+    }
+    /// Move one time step further in the simulation
+    pub fn step(&mut self) {
+        let mut events = Vec::new();
+
+        /* COLLISION DETECTION */
+        // only asteroids sharing a grid cell with a bullet or the player get the exact test
+        let grid = self.asteroid_grid();
+        // bullets can move further than a small asteroid is wide, so look along their whole path
+        let bullet_paths: Vec<(Vec2, f32)> = self
+            .bullets
+            .iter()
+            .map(|bullet| {
+                let motion = bullet.motion();
+                // the asteroid moved a little too
+                let reach = motion.length() / 2.0 + Asteroid::MAX_SPEED;
+                (bullet.position() - motion / 2.0, reach)
+            })
+            .collect();
+
+        let mut children = Vec::new();
+        for (asteroid_index, bullet_index) in grid.candidate_pairs(&bullet_paths) {
+            let asteroid = &mut self.asteroids[asteroid_index];
+            let bullet = &mut self.bullets[bullet_index];
+            // a bullet only splits one asteroid, and an asteroid only splits once
+            if asteroid.has_collided() || bullet.has_collided() {
+                continue;
+            }
+            // if the bullet passed through the asteroid since the last step
+            let motion = bullet.motion() - asteroid.velocity();
+            if asteroid.is_swept_point_inside(bullet.position(), motion, &self.arena) {
+                // calculate the children asteroids
+                let new_children = asteroid.split(bullet.velocity(), &self.config);
+
+                // collect the children
+                children.extend(new_children);
+
+                // destroy does NOT take ownership it just sets the has_collided field true
+                asteroid.set_collided();
+                bullet.set_collided();
+                events.push(GameEvent::AsteroidDestroyed {
+                    position: asteroid.position(),
+                    size: asteroid.size(),
+                    points: asteroid.points(),
+                });
+            }
+        }
+        if !self.player.is_invulnerable() && !self.is_god_mode {
+            let player_vertices = self.player.vertices();
+            for index in grid.candidates(self.player.position(), self.player.collider().radius()) {
+                let asteroid = &self.asteroids[index];
+                if asteroid.has_collided() {
+                    continue;
+                }
+                // a small asteroid can cross a side of the ship without covering a corner
+                if asteroid.overlaps_polygon(self.player.position(), &player_vertices, &self.arena)
+                {
+                    self.player.set_collided();
+                }
+            }
+        }
+
+        /* HANDLE COLLISION */
+        // add any children from the collisions
+        self.asteroids.append(&mut children);
+
+        // Only keep bullets and asteroids that are alive or valid.
+        self.asteroids.retain(Asteroid::is_alive);
+        self.bullets.retain(Bullet::is_alive);
+
+        // Is the player alive?
+        if self.player.has_collided() {
+            let position = self.player.position();
+            self.player.lose_life(&self.arena);
+            events.push(GameEvent::PlayerDied {
+                position,
+                lives_left: self.player.lives(),
+            });
+        }
+
+        /* REACT TO EVENTS */
+        let previous_score = self.score;
+        for event in &events {
+            self.apply_event(event);
+        }
+        if self.score / Self::EXTRA_LIFE_SCORE > previous_score / Self::EXTRA_LIFE_SCORE {
+            self.player.gain_life();
+            events.push(GameEvent::ExtraLifeGained {
+                lives: self.player.lives(),
+            });
+        }
+
+        // Are there any asteroids left?
+        if self.asteroids.is_empty() {
+            events.push(GameEvent::WaveCleared { wave: self.wave });
+            self.start_next_wave();
+        }
+        self.events.append(&mut events);
+
+        /* UPDATE GAME PHYSICS */
+        self.player.step(&self.arena, &self.config);
+        for asteroid in self.asteroids.iter_mut() {
+            asteroid.step(&self.arena);
+        }
+        for bullet in self.bullets.iter_mut() {
+            bullet.step(&self.arena);
+        }
+        if self.camera.mode() == CameraMode::Follow {
+            // friction stops the ship speeding up long before the max speed
+            let player = &self.config.player;
+            let top_speed = (player.thrust / player.friction).min(player.max_speed);
+            let speed = (self.player.speed() / top_speed).min(1.0);
+            self.camera
+                .zoom_to(1.0 + (GameCamera::TOP_SPEED_ZOOM - 1.0) * speed);
+        }
+        self.camera.step(self.player.position(), &self.arena);
+    }
+}
+impl World {
+    /// The world's own reactions: scoring and camera shake
+    fn apply_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::AsteroidDestroyed { size, points, .. } => {
+                self.score += points;
+                let impact = size / Asteroid::MAX_SIZE;
+                self.camera.shake(GameCamera::IMPACT_TRAUMA * impact);
+            }
+            GameEvent::PlayerDied { .. } => {
+                self.camera.shake(GameCamera::DEATH_TRAUMA);
+                self.camera.punch_zoom(GameCamera::DEATH_ZOOM);
+            }
+            _ => {}
+        }
+    }
+}
+impl World {
+    /// Heads up display with the score, lives and wave
+    fn draw_hud(&self, renderer: &mut dyn Renderer) {
+        let lines = [
+            format!("SCORE {}", self.score),
+            format!("LIVES {}", self.player.lives()),
+            format!("WAVE {}", self.wave),
+        ];
+        for (i, line) in lines.iter().enumerate() {
+            let position = vec2(10.0, Self::HUD_FONT_SIZE * (i + 1) as f32);
+            renderer.draw_text(line, position, Self::HUD_FONT_SIZE, WHITE);
+        }
+    }
+}
+impl World {
+    /// A map of the whole arena in the top right corner of the view, for when the camera only shows part of it
+    fn draw_minimap(&self, renderer: &mut dyn Renderer) {
+        const MARGIN: f32 = 10.0;
+        let size = vec2(Self::MINIMAP_SIZE, Self::MINIMAP_SIZE);
+        let corner = vec2(self.camera.view().x - size.x - MARGIN, MARGIN);
+        let scale = size / self.arena.dimensions();
+        let to_minimap = |point: Vec2| corner + point.rem_euclid(self.arena.dimensions()) * scale;
+
+        let border = [
+            corner,
+            corner + vec2(size.x, 0.0),
+            corner + size,
+            corner + vec2(0.0, size.y),
+        ];
+        renderer.draw_polygon(&border, Color::new(0.0, 0.0, 0.0, 0.6));
+        renderer.draw_polygon_lines(&border, 1.0, GRAY);
+
+        for asteroid in &self.asteroids {
+            let radius = (asteroid.size() * scale.min_element()).max(1.0);
+            renderer.draw_circle(to_minimap(asteroid.position()), radius, LIGHTGRAY);
+        }
+        renderer.draw_circle(to_minimap(self.player.position()), 2.0, YELLOW);
+    }
+    /// Draws the player, asteroids and bullets through the camera, with `draw` being [Draw::draw] or [Draw::draw_outline]
+    fn draw_objects(&self, renderer: &mut dyn Renderer, draw: fn(&dyn Draw, &mut dyn Renderer)) {
+        self.draw_in_arena(renderer, |renderer| {
+            draw(&self.player, renderer);
+            for asteroid in &self.asteroids {
+                draw(asteroid, renderer);
+            }
+            for bullet in &self.bullets {
+                draw(bullet, renderer);
+            }
+        });
+    }
+    /// Whatever `draw` draws is in arena coordinates, and goes through the camera
+    pub fn draw_in_arena(&self, renderer: &mut dyn Renderer, draw: impl FnOnce(&mut dyn Renderer)) {
+        let mut camera = self.camera.renderer(renderer, &self.arena);
+        let mut wrapping;
+        let renderer: &mut dyn Renderer = match self.camera.mode() {
+            // anything near an edge is also drawn on the other side, so it doesn't pop across when it wraps
+            CameraMode::Fixed => {
+                wrapping = WrappingRenderer::new(&mut camera, self.arena.dimensions());
+                &mut wrapping
+            }
+            // a following camera already draws everything at the copy closest to the view
+            CameraMode::Follow => &mut camera,
+        };
+        draw(renderer);
+    }
+}
+impl Draw for World {
+    /// The HUD isn't drawn through the camera, so it doesn't shake or zoom
+    fn draw(&self, renderer: &mut dyn Renderer) {
+        self.draw_objects(renderer, |thing, renderer| thing.draw(renderer));
+        self.draw_hud(renderer);
+        if self.camera.mode() == CameraMode::Follow {
+            self.draw_minimap(renderer);
+        }
+    }
+    fn draw_outline(&self, renderer: &mut dyn Renderer) {
+        self.draw_objects(renderer, |thing, renderer| thing.draw_outline(renderer));
+        self.draw_hud(renderer);
+        if self.camera.mode() == CameraMode::Follow {
+            self.draw_minimap(renderer);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::rand;
+    use std::sync::Mutex;
+
+    /// Tests that need the random numbers to repeat take turns, since they are shared by every thread
+    static RANDOM: Mutex<()> = Mutex::new(());
+
+    /// Thrusts, turns and fires for `frames` steps
+    fn play(world: &mut World, frames: usize) {
+        let actions = Actions {
+            rotate_right: true,
+            thrust: true,
+            fire: true,
+            ..Actions::default()
+        };
+        for _ in 0..frames {
+            world.apply_actions(actions);
+            world.step();
+            world.take_events();
+        }
+    }
+
+    #[test]
+    fn fast_bullet_cannot_tunnel_through_a_small_asteroid() {
+        let mut config = GameConfig::default();
+        config.bullet.speed = Asteroid::MIN_SIZE * 6.0;
+        for offset in [-6.0, -3.0, 0.0, 3.0, 6.0] {
+            let player = Player::new(vec2(100.0, 400.0));
+            let bullet = Bullet::fired_by(&player, &config);
+            // the bullet goes from one side to the other in its first move, without ever stopping inside
+            let center = bullet.position() + vec2(config.bullet.speed / 2.0, offset);
+            let asteroid = Asteroid::new(center, Vec2::ZERO, Asteroid::MIN_SIZE, 0.0, 0.0);
+            let mut world =
+                World::from_parts(Arena::default(), player, vec![asteroid], vec![bullet]);
+            world.set_config(config.clone());
+            world.set_god_mode(true);
+
+            world.step();
+            world.step();
+            let events = world.take_events();
+            assert!(
+                events
+                    .iter()
+                    .any(|event| matches!(event, GameEvent::AsteroidDestroyed { .. })),
+                "missed at offset {offset}"
+            );
+        }
+    }
+
+    #[test]
+    fn loaded_game_plays_on_exactly_like_the_saved_one() {
+        let _random = RANDOM.lock().unwrap();
+        rand::srand(1);
+        let mut world = World::default();
+        play(&mut world, 200);
+
+        let mut loaded = World::from_json(&world.to_json()).expect("just saved");
+        // what isn't saved, like whether the ship is thrusting for the sound, is set again by the next step
+        assert_eq!(loaded.to_json(), world.to_json());
+
+        rand::srand(2);
+        play(&mut world, 300);
+        rand::srand(2);
+        play(&mut loaded, 300);
+        assert_eq!(loaded, world);
+    }
+
+    #[test]
+    fn following_camera_zooms_out_at_speed() {
+        let mut world = World::new_large(Arena::default().dimensions());
+        world.set_god_mode(true);
+        let thrust = Actions {
+            thrust: true,
+            ..Actions::default()
+        };
+        for _ in 0..300 {
+            world.apply_actions(thrust);
+            world.step();
+        }
+        assert!(world.camera().zoom() < 0.8);
+    }
+
+    #[test]
+    fn save_with_another_version_is_not_loaded() {
+        let json = World::default().to_json().replacen(
+            &format!("\"version\":{}", World::SAVE_VERSION),
+            "\"version\":0",
+            1,
+        );
+        assert!(World::from_json(&json).is_none());
+    }
+
+    #[test]
+    fn small_asteroid_across_a_side_of_the_ship_kills_it() {
+        let player = Player::new(vec2(100.0, 100.0));
+        let [front, back, _] = player.vertices();
+        // centered just outside the middle of a long side, covering it but none of the corners
+        let side = back - front;
+        let outward = -side.perp().normalize();
+        let center = (front + back) / 2.0 + outward * 5.0;
+        let asteroid = Asteroid::new(center, Vec2::ZERO, Asteroid::MIN_SIZE, 0.0, 0.0);
+        assert!(player
+            .vertices()
+            .iter()
+            .all(|&vertex| !asteroid.is_point_inside(vertex, &Arena::default())));
+
+        let mut world = World::from_parts(Arena::default(), player, vec![asteroid], Vec::new());
+        world.step();
+        assert_eq!(world.player().lives(), Player::STARTING_LIVES - 1);
+    }
+}
@@ -0,0 +1,82 @@
+//! The arena wraps around at its edges, so anything crossing an edge is also on the other side
+//! - [ghost_offsets] finds where the extra copies of something are, up to four in a corner
+//! - [WrappingRenderer] draws every shape once for each of its copies
+//! - collisions check the copies too, see [crate::Asteroid::is_point_inside]
+
+use crate::{bounding_box, Renderer, Sprite};
+use macroquad::prelude::*;
+use std::f32::consts::FRAC_1_SQRT_2;
+
+/// How far to move something with the bounding box `min`..`max` to get each of its copies.
+/// The first offset is always zero, for the original
+pub fn ghost_offsets(min: Vec2, max: Vec2, arena: Vec2) -> Vec<Vec2> {
+    let wrap_offsets = |min: f32, max: f32, size: f32| {
+        let mut offsets = vec![0.0];
+        if min < 0.0 {
+            offsets.push(size);
+        }
+        if max > size {
+            offsets.push(-size);
+        }
+        offsets
+    };
+    let x_offsets = wrap_offsets(min.x, max.x, arena.x);
+    let y_offsets = wrap_offsets(min.y, max.y, arena.y);
+
+    y_offsets
+        .iter()
+        .flat_map(|&y| x_offsets.iter().map(move |&x| vec2(x, y)))
+        .collect()
+}
+
+/// Draws every shape into `inner` again on the other side of any edge it crosses. Text is only drawn once
+pub struct WrappingRenderer<'a> {
+    inner: &'a mut dyn Renderer,
+    arena: Vec2,
+}
+impl<'a> WrappingRenderer<'a> {
+    pub fn new(inner: &'a mut dyn Renderer, arena: Vec2) -> Self {
+        Self { inner, arena }
+    }
+    fn offsets_around(&self, center: Vec2, reach: f32) -> Vec<Vec2> {
+        ghost_offsets(center - reach, center + reach, self.arena)
+    }
+    fn polygon_offsets(&self, vertices: &[Vec2], padding: f32) -> Vec<Vec2> {
+        match bounding_box(vertices) {
+            Some((min, max)) => ghost_offsets(min - padding, max + padding, self.arena),
+            None => Vec::new(),
+        }
+    }
+}
+impl Renderer for WrappingRenderer<'_> {
+    fn draw_polygon(&mut self, vertices: &[Vec2], color: Color) {
+        for offset in self.polygon_offsets(vertices, 0.0) {
+            let moved: Vec<Vec2> = vertices.iter().map(|&vertex| vertex + offset).collect();
+            self.inner.draw_polygon(&moved, color);
+        }
+    }
+    fn draw_polygon_lines(&mut self, vertices: &[Vec2], thickness: f32, color: Color) {
+        for offset in self.polygon_offsets(vertices, thickness / 2.0) {
+            let moved: Vec<Vec2> = vertices.iter().map(|&vertex| vertex + offset).collect();
+            self.inner.draw_polygon_lines(&moved, thickness, color);
+        }
+    }
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        for offset in self.offsets_around(center, radius) {
+            self.inner.draw_circle(center + offset, radius, color);
+        }
+    }
+    fn draw_sprite(&mut self, sprite: Sprite, center: Vec2, size: f32, rotation: f32) {
+        // a rotated square always fits inside the circle through its corners
+        for offset in self.offsets_around(center, size * FRAC_1_SQRT_2) {
+            self.inner
+                .draw_sprite(sprite, center + offset, size, rotation);
+        }
+    }
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        self.inner.draw_text(text, position, font_size, color);
+    }
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        self.inner.measure_text(text, font_size)
+    }
+}
@@ -0,0 +1,124 @@
+//! Golden image tests: draws fixed scenes with the [SoftwareRenderer] and compares them to the pngs in `tests/golden`
+//! - `cargo test` checks every scene, and fails if any of them changed
+//! - `BLESS=1 cargo test --test golden` replaces the reference images with what is drawn now
+//! - a scene that doesn't match is written next to its reference as `<name>.actual.png`
+
+use asteroids::{
+    count_different_pixels, load_png, Arena, Asteroid, Bullet, Draw, GameConfig, Player,
+    SoftwareRenderer, World, SCREEN_HEIGHT, SCREEN_WIDTH,
+};
+use macroquad::prelude::*;
+use std::path::PathBuf;
+
+const FOLDER: &str = "tests/golden";
+/// How much a channel can be off by before the pixel counts as different
+const TOLERANCE: u8 = 2;
+
+#[test]
+fn asteroid() {
+    check_scene("asteroid", 200, |renderer| new_asteroid().draw(renderer));
+}
+#[test]
+fn asteroid_outline() {
+    check_scene("asteroid_outline", 200, |renderer| {
+        new_asteroid().draw_outline(renderer)
+    });
+}
+#[test]
+fn player() {
+    check_scene("player", 100, |renderer| new_player().draw(renderer));
+}
+#[test]
+fn player_outline() {
+    check_scene("player_outline", 100, |renderer| {
+        new_player().draw_outline(renderer)
+    });
+}
+#[test]
+fn hud() {
+    check_scene("hud", world_size(), |renderer| {
+        new_world().draw_outline(renderer)
+    });
+}
+#[test]
+fn wrapped_corner() {
+    check_scene("wrapped_corner", world_size(), |renderer| {
+        new_wrapped_world().draw_outline(renderer)
+    });
+}
+
+/// Big enough for the whole arena
+fn world_size() -> u16 {
+    SCREEN_WIDTH.min(SCREEN_HEIGHT) as u16
+}
+
+fn new_asteroid() -> Asteroid {
+    Asteroid::new(vec2(100.0, 100.0), Vec2::ZERO, 60.0, 0.3, 0.0)
+}
+
+fn new_player() -> Player {
+    Player::new(vec2(50.0, 50.0))
+}
+
+fn new_world() -> World {
+    let player = Player::new(vec2(400.0, 400.0));
+    let bullets = vec![Bullet::fired_by(&player, &GameConfig::default())];
+    let asteroids = vec![
+        Asteroid::new(vec2(200.0, 550.0), Vec2::ZERO, Asteroid::MAX_SIZE, 1.0, 0.0),
+        Asteroid::new(
+            vec2(600.0, 250.0),
+            Vec2::ZERO,
+            Asteroid::MIN_SIZE * 2.0,
+            2.0,
+            0.0,
+        ),
+    ];
+    World::from_parts(Arena::default(), player, asteroids, bullets)
+}
+
+/// An asteroid over the bottom left corner, so it shows up in all four corners
+fn new_wrapped_world() -> World {
+    let player = Player::new(vec2(400.0, 400.0));
+    let asteroids = vec![Asteroid::new(
+        vec2(20.0, 780.0),
+        Vec2::ZERO,
+        Asteroid::MAX_SIZE,
+        0.5,
+        0.0,
+    )];
+    World::from_parts(Arena::default(), player, asteroids, Vec::new())
+}
+
+/// Draws a `size` by `size` scene and compares it to `<name>.png`, or replaces that with it when blessing
+fn check_scene(name: &str, size: u16, draw: impl FnOnce(&mut SoftwareRenderer)) {
+    let mut renderer = SoftwareRenderer::new(size, size);
+    draw(&mut renderer);
+
+    let reference_path = PathBuf::from(FOLDER).join(format!("{name}.png"));
+    let actual_path = PathBuf::from(FOLDER).join(format!("{name}.actual.png"));
+    if std::env::var_os("BLESS").is_some() {
+        renderer
+            .save_png(&reference_path)
+            .expect("failed to write reference image");
+        return;
+    }
+
+    let reference = load_png(&reference_path).unwrap_or_else(|error| {
+        panic!("{name}: no reference image ({error}), run with BLESS=1 to create it")
+    });
+    let problem = match count_different_pixels(renderer.frame(), &reference, TOLERANCE) {
+        Some(0) => {
+            let _ = std::fs::remove_file(&actual_path);
+            return;
+        }
+        Some(count) => format!("{count} pixels are different"),
+        None => String::from("the size changed"),
+    };
+    renderer
+        .save_png(&actual_path)
+        .expect("failed to write actual image");
+    panic!(
+        "{name}: {problem}, see {}. if that was on purpose, rerun with BLESS=1",
+        actual_path.display()
+    );
+}